@@ -73,7 +73,7 @@ impl<'a> KTable<'a> {
     pub fn remove(&mut self, key: &'a str) {
         let index: usize = self.hash(key, self.size as i32) as usize;
         let mut x: i32 = 0;
-        let probing_index: usize = self.probing(index as usize, x, self.size) as usize;
+        let probing_index: usize = self.probing(index, x, self.size) as usize;
 
         while let Some(_slot) = self.cycles.get_mut(probing_index) {
             let current_index: usize = self.probing(index, x, self.size) as usize;
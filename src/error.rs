@@ -0,0 +1,111 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Geometry,
+    TessControl,
+    TessEvaluation,
+    Compute,
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+            ShaderStage::Geometry => write!(f, "geometry"),
+            ShaderStage::TessControl => write!(f, "tessellation control"),
+            ShaderStage::TessEvaluation => write!(f, "tessellation evaluation"),
+            ShaderStage::Compute => write!(f, "compute"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ShaderCompile { stage: ShaderStage, log: String },
+    UnknownShaderStage { stage: u32 },
+    ShaderLink { log: String },
+    UniformNotFound { name: String },
+    ImageLoad { path: String, source: image::ImageError },
+    FontLoad { path: String, source: std::io::Error },
+    FontParse,
+    TextureData { expected: usize, actual: usize },
+    TextureBounds { x: u32, y: u32, width: u32, height: u32 },
+    TextureSize { width: u32, height: u32, max: u32 },
+    GlfwInit(glfw::InitError),
+    WindowCreation,
+    Io { path: String, source: std::io::Error },
+    ImageSave { path: String, source: image::ImageError },
+    Json { path: String, source: serde_json::Error },
+    AtlasOverflow { name: String, width: u32, height: u32 },
+    AtlasLayout { path: String, message: String },
+    MapFormat { path: String, message: String },
+    UnsupportedMapFeature { path: String, feature: String },
+    AutomatonRule { rule: String, message: String },
+    AnimationFrame { position: usize, duration: f32 },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ShaderCompile { stage, log } => write!(f, "{} shader compilation failed: {}", stage, log),
+            Error::UnknownShaderStage { stage } => write!(f, "Unknown shader stage 0x{:04X}", stage),
+            Error::ShaderLink { log } => write!(f, "Shader program linking failed: {}", log),
+            Error::UniformNotFound { name } => write!(f, "Cannot locate uniform: {}", name),
+            Error::ImageLoad { path, source } => write!(f, "Error on loading image {}: {}", path, source),
+            Error::FontLoad { path, source } => write!(f, "Error on loading font {}: {}", path, source),
+            Error::FontParse => write!(f, "Error on parsing font data"),
+            Error::TextureData { expected, actual } => {
+                write!(f, "Texture data has {} bytes, expected {}", actual, expected)
+            }
+            Error::TextureBounds { x, y, width, height } => {
+                write!(f, "Sub-image {}x{} at ({}, {}) is out of texture bounds", width, height, x, y)
+            }
+            Error::TextureSize { width, height, max } => {
+                write!(f, "Texture size {}x{} exceeds the maximum of {}", width, height, max)
+            }
+            Error::GlfwInit(error) => write!(f, "Failed to initialize GLFW: {}", error),
+            Error::WindowCreation => write!(f, "Failed to create GLFW window"),
+            Error::Io { path, source } => write!(f, "I/O error on {}: {}", path, source),
+            Error::ImageSave { path, source } => write!(f, "Error on saving image {}: {}", path, source),
+            Error::Json { path, source } => write!(f, "Invalid JSON in {}: {}", path, source),
+            Error::AtlasOverflow { name, width, height } => {
+                write!(f, "Image {} does not fit in a {}x{} atlas page", name, width, height)
+            }
+            Error::AtlasLayout { path, message } => write!(f, "Invalid atlas layout {}: {}", path, message),
+            Error::MapFormat { path, message } => write!(f, "Invalid map {}: {}", path, message),
+            Error::UnsupportedMapFeature { path, feature } => {
+                write!(f, "Map {} uses an unsupported feature: {}", path, feature)
+            }
+            Error::AutomatonRule { rule, message } => write!(f, "Invalid automaton rule '{}': {}", rule, message),
+            Error::AnimationFrame { position, duration } => {
+                write!(f, "Animation frame {} has an invalid duration of {} seconds", position, duration)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ImageLoad { source, .. } => Some(source),
+            Error::FontLoad { source, .. } => Some(source),
+            Error::GlfwInit(error) => Some(error),
+            Error::Io { source, .. } => Some(source),
+            Error::ImageSave { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<glfw::InitError> for Error {
+    fn from(error: glfw::InitError) -> Self {
+        Error::GlfwInit(error)
+    }
+}
@@ -0,0 +1,205 @@
+use crate::error::{Error, Result};
+
+use super::sprite::{Sprite, SpriteSheet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    PingPong,
+    Once,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+    pub index: usize,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationEvent {
+    pub name: String,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<AnimationFrame>,
+    events: Vec<AnimationEvent>,
+    pub mode: PlayMode,
+    pub speed: f32,
+    position: usize,
+    elapsed: f32,
+    reversing: bool,
+    playing: bool,
+    finished: bool,
+}
+
+impl Animation {
+    pub fn new(frames: &[usize], frame_duration: f32, mode: PlayMode) -> Result<Self> {
+        let frames: Vec<AnimationFrame> = frames
+            .iter()
+            .map(|&index| AnimationFrame { index, duration: frame_duration })
+            .collect();
+        Self::from_frames(frames, mode)
+    }
+
+    // Durations must be finite and positive, otherwise `update` could never leave a frame.
+    pub fn from_frames(frames: Vec<AnimationFrame>, mode: PlayMode) -> Result<Self> {
+        if let Some((position, frame)) = frames
+            .iter()
+            .enumerate()
+            .find(|(_, frame)| !frame.duration.is_finite() || frame.duration <= 0.0)
+        {
+            return Err(Error::AnimationFrame { position, duration: frame.duration });
+        }
+        Ok(Self {
+            frames,
+            events: Vec::new(),
+            mode,
+            speed: 1.0,
+            position: 0,
+            elapsed: 0.0,
+            reversing: false,
+            playing: true,
+            finished: false,
+        })
+    }
+
+    pub fn with_event(mut self, position: usize, name: &str) -> Self {
+        self.add_event(position, name);
+        self
+    }
+
+    pub fn add_event(&mut self, position: usize, name: &str) {
+        self.events.push(AnimationEvent { name: name.to_string(), position });
+    }
+
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current_frame(&self) -> Option<usize> {
+        self.frames.get(self.position).map(|frame| frame.index)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.elapsed = 0.0;
+        self.reversing = false;
+        self.finished = false;
+    }
+
+    pub fn total_duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    // Advances by `delta_time` seconds and returns the events of every frame entered along the way.
+    pub fn update(&mut self, delta_time: f32) -> Vec<AnimationEvent> {
+        let mut fired = Vec::new();
+        if !self.playing || self.finished || self.frames.is_empty() {
+            return fired;
+        }
+
+        let step = delta_time * self.speed.max(0.0);
+        if !step.is_finite() {
+            return fired;
+        }
+        self.elapsed += step;
+
+        loop {
+            let duration = self.frames[self.position].duration;
+            if self.elapsed < duration {
+                break;
+            }
+            self.elapsed -= duration;
+            if !self.advance() {
+                self.finished = true;
+                self.elapsed = 0.0;
+                break;
+            }
+            fired.extend(self.events.iter().filter(|event| event.position == self.position).cloned());
+        }
+        fired
+    }
+
+    fn advance(&mut self) -> bool {
+        let last = self.frames.len() - 1;
+        match self.mode {
+            PlayMode::Loop => {
+                self.position = if self.position == last { 0 } else { self.position + 1 };
+                true
+            }
+            PlayMode::Once => {
+                if self.position == last {
+                    return false;
+                }
+                self.position += 1;
+                true
+            }
+            PlayMode::PingPong => {
+                if last == 0 {
+                    return true;
+                }
+                if self.reversing && self.position == 0 {
+                    self.reversing = false;
+                } else if !self.reversing && self.position == last {
+                    self.reversing = true;
+                }
+                self.position = if self.reversing { self.position - 1 } else { self.position + 1 };
+                true
+            }
+        }
+    }
+
+    pub fn apply(&self, sheet: &SpriteSheet, sprite: &mut Sprite) {
+        if let Some(index) = self.current_frame() {
+            sprite.set_frame(sheet, index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_need_a_finite_positive_duration() {
+        for duration in [0.0, -0.1, f32::NAN, f32::INFINITY] {
+            assert!(matches!(Animation::new(&[0, 1], duration, PlayMode::Loop), Err(Error::AnimationFrame { .. })));
+        }
+        let frames = vec![AnimationFrame { index: 0, duration: 0.1 }, AnimationFrame { index: 1, duration: 0.0 }];
+        assert!(matches!(Animation::from_frames(frames, PlayMode::Loop), Err(Error::AnimationFrame { position: 1, .. })));
+    }
+
+    #[test]
+    fn updates_advance_through_frames() {
+        let mut animation = Animation::new(&[4, 5, 6], 0.1, PlayMode::Loop).unwrap().with_event(2, "step");
+        assert!(animation.update(0.15).is_empty());
+        assert_eq!(animation.current_frame(), Some(5));
+        assert_eq!(animation.update(0.1).len(), 1);
+        assert_eq!(animation.current_frame(), Some(6));
+        assert!(animation.update(f32::INFINITY).is_empty());
+        assert_eq!(animation.current_frame(), Some(6));
+    }
+}
@@ -0,0 +1,423 @@
+use std::{collections::HashMap, fs, path::Path, rc::Rc};
+
+use image::{DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use super::{gl_wrapper::{Texture2D, TextureOptions}, sprite::{SpriteSheet, TextureRegion}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() && other.y < self.bottom()
+    }
+
+    fn contains(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+}
+
+// MaxRects bin with the best-short-side-fit heuristic.
+struct MaxRects {
+    free: Vec<Rect>,
+    used_width: u32,
+    used_height: u32,
+}
+
+impl MaxRects {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            free: vec![Rect { x: 0, y: 0, width, height }],
+            used_width: 0,
+            used_height: 0,
+        }
+    }
+
+    fn insert(&mut self, width: u32, height: u32) -> Option<Rect> {
+        let placed = self
+            .free
+            .iter()
+            .filter(|free| free.width >= width && free.height >= height)
+            .min_by_key(|free| {
+                let leftover_x = free.width - width;
+                let leftover_y = free.height - height;
+                (leftover_x.min(leftover_y), leftover_x.max(leftover_y))
+            })
+            .map(|free| Rect { x: free.x, y: free.y, width, height })?;
+
+        let mut split = Vec::new();
+        self.free.retain(|free| {
+            if !free.intersects(&placed) {
+                return true;
+            }
+            if placed.x > free.x {
+                split.push(Rect { width: placed.x - free.x, ..*free });
+            }
+            if placed.right() < free.right() {
+                split.push(Rect { x: placed.right(), width: free.right() - placed.right(), ..*free });
+            }
+            if placed.y > free.y {
+                split.push(Rect { height: placed.y - free.y, ..*free });
+            }
+            if placed.bottom() < free.bottom() {
+                split.push(Rect { y: placed.bottom(), height: free.bottom() - placed.bottom(), ..*free });
+            }
+            false
+        });
+        self.free.extend(split);
+
+        let mut index = 0;
+        while index < self.free.len() {
+            let candidate = self.free[index];
+            let redundant = self
+                .free
+                .iter()
+                .enumerate()
+                .any(|(other, free)| other != index && free.contains(&candidate) && (free != &candidate || other < index));
+            if redundant {
+                self.free.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        self.used_width = self.used_width.max(placed.right());
+        self.used_height = self.used_height.max(placed.bottom());
+        Some(placed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasEntry {
+    pub page: usize,
+    pub region: TextureRegion,
+}
+
+pub struct AtlasBuilder {
+    page_width: u32,
+    page_height: u32,
+    padding: u32,
+    extrusion: u32,
+    images: Vec<(String, RgbaImage)>,
+}
+
+impl AtlasBuilder {
+    pub fn new(page_width: u32, page_height: u32) -> Self {
+        Self {
+            page_width,
+            page_height,
+            padding: 0,
+            extrusion: 0,
+            images: Vec::new(),
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_extrusion(mut self, extrusion: u32) -> Self {
+        self.extrusion = extrusion;
+        self
+    }
+
+    // Adding a name again replaces the earlier image, so the last one wins.
+    pub fn add(&mut self, name: &str, image: &DynamicImage) {
+        let image = image.to_rgba8();
+        match self.images.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = image,
+            None => self.images.push((name.to_string(), image)),
+        }
+    }
+
+    pub fn add_file(&mut self, name: &str, image_path: &str) -> Result<()> {
+        let image = image::open(image_path).map_err(|source| Error::ImageLoad {
+            path: image_path.to_string(),
+            source,
+        })?;
+        self.add(name, &image);
+        Ok(())
+    }
+
+    pub fn build(mut self) -> Result<Atlas> {
+        let border = self.extrusion * 2 + self.padding;
+        self.images.sort_by_key(|(_, image)| std::cmp::Reverse(image.width().max(image.height())));
+
+        let mut bins: Vec<MaxRects> = Vec::new();
+        let mut placements = Vec::with_capacity(self.images.len());
+        for (name, image) in &self.images {
+            let width = image.width() + border;
+            let height = image.height() + border;
+            if width > self.page_width + self.padding || height > self.page_height + self.padding {
+                return Err(Error::AtlasOverflow {
+                    name: name.clone(),
+                    width: self.page_width,
+                    height: self.page_height,
+                });
+            }
+
+            let placed = bins.iter_mut().enumerate().find_map(|(page, bin)| bin.insert(width, height).map(|rect| (page, rect)));
+            let (page, rect) = match placed {
+                Some(placed) => placed,
+                None => {
+                    let mut bin = MaxRects::new(self.page_width + self.padding, self.page_height + self.padding);
+                    let rect = bin.insert(width, height).expect("image fits an empty page");
+                    bins.push(bin);
+                    (bins.len() - 1, rect)
+                }
+            };
+            placements.push((page, rect));
+        }
+
+        let mut pages: Vec<RgbaImage> = bins
+            .iter()
+            .map(|bin| {
+                RgbaImage::new(
+                    bin.used_width.saturating_sub(self.padding).max(1),
+                    bin.used_height.saturating_sub(self.padding).max(1),
+                )
+            })
+            .collect();
+
+        let mut entries = HashMap::with_capacity(self.images.len());
+        for ((name, image), (page, rect)) in self.images.iter().zip(placements) {
+            let x = rect.x + self.extrusion;
+            let y = rect.y + self.extrusion;
+            blit_extruded(&mut pages[page], image, x, y, self.extrusion);
+            entries.insert(name.clone(), AtlasEntry {
+                page,
+                region: TextureRegion::new(x, y, image.width(), image.height()),
+            });
+        }
+
+        Ok(Atlas { pages, entries })
+    }
+}
+
+fn blit_extruded(page: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32, extrusion: u32) {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return;
+    }
+    let left = x - extrusion;
+    let top = y - extrusion;
+    for target_y in 0..height + extrusion * 2 {
+        let source_y = target_y.saturating_sub(extrusion).min(height - 1);
+        for target_x in 0..width + extrusion * 2 {
+            let source_x = target_x.saturating_sub(extrusion).min(width - 1);
+            page.put_pixel(left + target_x, top + target_y, *image.get_pixel(source_x, source_y));
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AtlasLayout {
+    pages: Vec<PageLayout>,
+    frames: Vec<FrameLayout>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PageLayout {
+    file: String,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FrameLayout {
+    name: String,
+    page: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+pub struct Atlas {
+    pages: Vec<RgbaImage>,
+    entries: HashMap<String, AtlasEntry>,
+}
+
+impl Atlas {
+    pub fn pages(&self) -> &[RgbaImage] {
+        &self.pages
+    }
+
+    pub fn entry(&self, name: &str) -> Option<AtlasEntry> {
+        self.entries.get(name).copied()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn uv(&self, name: &str) -> Option<[f32; 4]> {
+        let entry = self.entry(name)?;
+        let page = &self.pages[entry.page];
+        let (width, height) = (page.width() as f32, page.height() as f32);
+        let region = entry.region;
+        Some([
+            region.x as f32 / width,
+            region.y as f32 / height,
+            (region.x + region.width) as f32 / width,
+            (region.y + region.height) as f32 / height,
+        ])
+    }
+
+    pub fn upload(&self, options: TextureOptions) -> Result<Vec<SpriteSheet>> {
+        let mut sheets = Vec::with_capacity(self.pages.len());
+        for page in &self.pages {
+            let texture = Texture2D::from_rgba(page.width(), page.height(), page.as_raw(), options)?;
+            sheets.push(SpriteSheet::new(Rc::new(texture)));
+        }
+
+        let mut names: Vec<&String> = self.entries.keys().collect();
+        names.sort();
+        for name in names {
+            let entry = self.entries[name];
+            sheets[entry.page].add_frame(name, entry.region);
+        }
+        Ok(sheets)
+    }
+
+    // Writes `<stem>_<page>.png` next to the layout file so the JSON only stores relative names.
+    pub fn save(&self, layout_path: &str) -> Result<()> {
+        let path = Path::new(layout_path);
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("atlas");
+
+        let mut pages = Vec::with_capacity(self.pages.len());
+        for (index, page) in self.pages.iter().enumerate() {
+            let file = format!("{}_{}.png", stem, index);
+            let page_path = directory.join(&file);
+            page.save(&page_path).map_err(|source| Error::ImageSave {
+                path: page_path.display().to_string(),
+                source,
+            })?;
+            pages.push(PageLayout { file, width: page.width(), height: page.height() });
+        }
+
+        let mut frames: Vec<FrameLayout> = self
+            .entries
+            .iter()
+            .map(|(name, entry)| FrameLayout {
+                name: name.clone(),
+                page: entry.page,
+                x: entry.region.x,
+                y: entry.region.y,
+                width: entry.region.width,
+                height: entry.region.height,
+            })
+            .collect();
+        frames.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let json = serde_json::to_string_pretty(&AtlasLayout { pages, frames }).map_err(|source| Error::Json {
+            path: layout_path.to_string(),
+            source,
+        })?;
+        fs::write(path, json).map_err(|source| Error::Io { path: layout_path.to_string(), source })
+    }
+
+    pub fn load(layout_path: &str) -> Result<Atlas> {
+        let path = Path::new(layout_path);
+        let json = fs::read_to_string(path).map_err(|source| Error::Io { path: layout_path.to_string(), source })?;
+        let layout: AtlasLayout = serde_json::from_str(&json).map_err(|source| Error::Json {
+            path: layout_path.to_string(),
+            source,
+        })?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut pages = Vec::with_capacity(layout.pages.len());
+        for page in &layout.pages {
+            let page_path = directory.join(&page.file);
+            let image = image::open(&page_path).map_err(|source| Error::ImageLoad {
+                path: page_path.display().to_string(),
+                source,
+            })?;
+            pages.push(image.to_rgba8());
+        }
+
+        let invalid = |message: String| Error::AtlasLayout { path: layout_path.to_string(), message };
+        let mut entries = HashMap::with_capacity(layout.frames.len());
+        for frame in layout.frames {
+            let page = pages
+                .get(frame.page)
+                .ok_or_else(|| invalid(format!("frame '{}' is on missing page {}", frame.name, frame.page)))?;
+            let right = frame.x.checked_add(frame.width).filter(|&right| right <= page.width());
+            let bottom = frame.y.checked_add(frame.height).filter(|&bottom| bottom <= page.height());
+            if right.is_none() || bottom.is_none() {
+                return Err(invalid(format!(
+                    "frame '{}' ({}x{} at {}, {}) is outside its {}x{} page",
+                    frame.name,
+                    frame.width,
+                    frame.height,
+                    frame.x,
+                    frame.y,
+                    page.width(),
+                    page.height()
+                )));
+            }
+            let region = TextureRegion::new(frame.x, frame.y, frame.width, frame.height);
+            if entries.insert(frame.name.clone(), AtlasEntry { page: frame.page, region }).is_some() {
+                return Err(invalid(format!("frame '{}' is listed twice", frame.name)));
+            }
+        }
+        Ok(Atlas { pages, entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, image::Rgba([255; 4])))
+    }
+
+    #[test]
+    fn adding_a_name_again_replaces_the_image() {
+        let mut builder = AtlasBuilder::new(64, 64);
+        builder.add("hero", &solid(8, 8));
+        builder.add("hero", &solid(16, 4));
+        let atlas = builder.build().unwrap();
+        assert_eq!(atlas.names().count(), 1);
+        let region = atlas.entry("hero").unwrap().region;
+        assert_eq!((region.width, region.height), (16, 4));
+    }
+
+    #[test]
+    fn load_rejects_frames_outside_their_page() {
+        let directory = std::env::temp_dir().join(format!("kern_atlas_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let layout_path = directory.join("atlas.json");
+        let layout_path = layout_path.to_str().unwrap();
+
+        let mut builder = AtlasBuilder::new(32, 32);
+        builder.add("tile", &solid(8, 8));
+        builder.build().unwrap().save(layout_path).unwrap();
+        assert!(Atlas::load(layout_path).is_ok());
+
+        let json = fs::read_to_string(layout_path).unwrap();
+        for (from, to) in [("\"page\": 0", "\"page\": 1"), ("\"x\": 0", "\"x\": 4294967295")] {
+            fs::write(layout_path, json.replace(from, to)).unwrap();
+            assert!(matches!(Atlas::load(layout_path), Err(Error::AtlasLayout { .. })), "{}", to);
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
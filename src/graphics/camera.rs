@@ -0,0 +1,147 @@
+use cgmath::{ortho, Matrix4, Rad, SquareMatrix, Vector2, Vector3, Vector4};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateSystem {
+    Ndc,
+    Pixels,
+    Virtual { width: f32, height: f32 },
+}
+
+impl CoordinateSystem {
+    pub fn is_y_down(&self) -> bool {
+        !matches!(self, CoordinateSystem::Ndc)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    pub position: Vector2<f32>,
+    pub zoom: f32,
+    pub rotation: f32,
+    coordinate_system: CoordinateSystem,
+    framebuffer_width: f32,
+    framebuffer_height: f32,
+}
+
+impl Camera2D {
+    pub fn new(viewport_width: u32, viewport_height: u32) -> Self {
+        Camera2D {
+            position: Vector2::new(0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            coordinate_system: CoordinateSystem::Ndc,
+            framebuffer_width: viewport_width.max(1) as f32,
+            framebuffer_height: viewport_height.max(1) as f32,
+        }
+    }
+
+    pub fn with_coordinate_system(mut self, coordinate_system: CoordinateSystem) -> Self {
+        self.coordinate_system = coordinate_system;
+        self
+    }
+
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        self.coordinate_system
+    }
+
+    pub fn set_coordinate_system(&mut self, coordinate_system: CoordinateSystem) {
+        self.coordinate_system = coordinate_system;
+    }
+
+    pub fn set_viewport(&mut self, width: u32, height: u32) {
+        self.framebuffer_width = width.max(1) as f32;
+        self.framebuffer_height = height.max(1) as f32;
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        match self.coordinate_system {
+            CoordinateSystem::Virtual { width, height } => {
+                let scale = (self.framebuffer_width / width).min(self.framebuffer_height / height);
+                let scaled_width = width * scale;
+                let scaled_height = height * scale;
+                Viewport {
+                    x: (self.framebuffer_width - scaled_width) / 2.0,
+                    y: (self.framebuffer_height - scaled_height) / 2.0,
+                    width: scaled_width,
+                    height: scaled_height,
+                }
+            }
+            _ => Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: self.framebuffer_width,
+                height: self.framebuffer_height,
+            },
+        }
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        let viewport = self.viewport();
+        viewport.width / viewport.height
+    }
+
+    pub fn world_size(&self) -> (f32, f32) {
+        match self.coordinate_system {
+            CoordinateSystem::Ndc => (2.0 * self.aspect_ratio(), 2.0),
+            CoordinateSystem::Pixels => (self.framebuffer_width, self.framebuffer_height),
+            CoordinateSystem::Virtual { width, height } => (width, height),
+        }
+    }
+
+    fn world_center(&self) -> Vector2<f32> {
+        match self.coordinate_system {
+            CoordinateSystem::Ndc => Vector2::new(0.0, 0.0),
+            _ => {
+                let (width, height) = self.world_size();
+                Vector2::new(width / 2.0, height / 2.0)
+            }
+        }
+    }
+
+    pub fn projection(&self) -> Matrix4<f32> {
+        let (width, height) = self.world_size();
+        match self.coordinate_system {
+            CoordinateSystem::Ndc => ortho(-width / 2.0, width / 2.0, -1.0, 1.0, -1.0, 1.0),
+            _ => ortho(0.0, width, height, 0.0, -1.0, 1.0),
+        }
+    }
+
+    pub fn view(&self) -> Matrix4<f32> {
+        let center = self.world_center();
+        let rotation = if self.coordinate_system.is_y_down() { self.rotation } else { -self.rotation };
+        Matrix4::from_translation(Vector3::new(center.x, center.y, 0.0))
+            * Matrix4::from_nonuniform_scale(self.zoom, self.zoom, 1.0)
+            * Matrix4::from_angle_z(Rad(rotation))
+            * Matrix4::from_translation(Vector3::new(-center.x - self.position.x, -center.y - self.position.y, 0.0))
+    }
+
+    pub fn view_projection(&self) -> Matrix4<f32> {
+        self.projection() * self.view()
+    }
+
+    pub fn screen_to_world(&self, screen_x: f32, screen_y: f32) -> Vector2<f32> {
+        let viewport = self.viewport();
+        let ndc_x = 2.0 * (screen_x - viewport.x) / viewport.width - 1.0;
+        let ndc_y = 1.0 - 2.0 * (screen_y - viewport.y) / viewport.height;
+        let inverse = self.view_projection().invert().unwrap_or_else(Matrix4::identity);
+        let world = inverse * Vector4::new(ndc_x, ndc_y, 0.0, 1.0);
+        Vector2::new(world.x, world.y)
+    }
+
+    pub fn world_to_screen(&self, world_x: f32, world_y: f32) -> Vector2<f32> {
+        let viewport = self.viewport();
+        let clip = self.view_projection() * Vector4::new(world_x, world_y, 0.0, 1.0);
+        Vector2::new(
+            viewport.x + (clip.x + 1.0) * 0.5 * viewport.width,
+            viewport.y + (1.0 - clip.y) * 0.5 * viewport.height,
+        )
+    }
+}
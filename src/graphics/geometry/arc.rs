@@ -0,0 +1,171 @@
+use std::f32::consts::TAU;
+
+use cgmath::Vector2;
+
+use crate::graphics::{renderer::{Material, Primitive, Renderer2D, Vertex2D}, transform::Transform2D};
+use super::{collision::{convex_hull, Collider, ConvexPolygon}, ellipse::submit_sdf_quad, CurveMode, PhysicalObject};
+
+const COLLIDER_SEGMENTS: u32 = 32;
+
+pub struct KArc {
+    pub transform: Transform2D,
+    pub radius: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub thickness: f32,
+    pub color: [f32; 4],
+    pub mode: CurveMode,
+}
+
+impl KArc {
+    pub fn new(x: f32, y: f32, radius: f32, start_angle: f32, end_angle: f32, thickness: f32, color: [f32; 4]) -> Self {
+        Self {
+            transform: Transform2D::new(x, y),
+            radius,
+            start_angle,
+            end_angle,
+            thickness,
+            color,
+            mode: CurveMode::default(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: CurveMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn sweep(&self) -> f32 {
+        (self.end_angle - self.start_angle).clamp(-TAU, TAU)
+    }
+
+    pub fn inner_radius(&self) -> f32 {
+        (self.radius - self.thickness).max(0.0)
+    }
+
+    // A zero radius covers nothing, and the SDF would divide by it.
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        if self.radius <= 0.0 {
+            return;
+        }
+        match self.mode {
+            CurveMode::Segments(segments) => {
+                let (vertices, indices) = self.tessellate(segments.max(1));
+                renderer.submit_transformed(Primitive::Triangles, Material::Color, &vertices, &indices, &self.transform);
+            }
+            CurveMode::Sdf => {
+                let sweep = self.sweep();
+                let (start, sweep) = if sweep < 0.0 { (self.start_angle + sweep, -sweep) } else { (self.start_angle, sweep) };
+                let params = [self.inner_radius() / self.radius, start.rem_euclid(TAU), sweep, 1.0];
+                submit_sdf_quad(renderer, self.radius, self.radius, self.color, params, &self.transform);
+            }
+        }
+    }
+
+    fn tessellate(&self, segments: u32) -> (Vec<Vertex2D>, Vec<u32>) {
+        let inner_radius = self.inner_radius();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        if inner_radius <= 0.0 {
+            vertices.push(Vertex2D::colored(0.0, 0.0, self.color));
+            for point in self.arc_points(self.radius, segments) {
+                vertices.push(Vertex2D::colored(point.x, point.y, self.color));
+            }
+            for i in 0..segments {
+                indices.extend_from_slice(&[0, i + 1, i + 2]);
+            }
+        } else {
+            let outer = self.arc_points(self.radius, segments);
+            let inner = self.arc_points(inner_radius, segments);
+            for (outer, inner) in outer.iter().zip(inner.iter()) {
+                vertices.push(Vertex2D::colored(outer.x, outer.y, self.color));
+                vertices.push(Vertex2D::colored(inner.x, inner.y, self.color));
+            }
+            for i in 0..segments {
+                let base = i * 2;
+                indices.extend_from_slice(&[base, base + 2, base + 1, base + 1, base + 2, base + 3]);
+            }
+        }
+        (vertices, indices)
+    }
+
+    fn arc_points(&self, radius: f32, segments: u32) -> Vec<Vector2<f32>> {
+        let sweep = self.sweep();
+        (0..=segments)
+            .map(|i| {
+                let angle = self.start_angle + sweep * i as f32 / segments as f32;
+                Vector2::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect()
+    }
+}
+
+impl PhysicalObject for KArc {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KArc::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        let mut points = self.arc_points(self.radius, COLLIDER_SEGMENTS);
+        let inner_radius = self.inner_radius();
+        if inner_radius <= 0.0 {
+            points.push(Vector2::new(0.0, 0.0));
+        } else {
+            points.extend(self.arc_points(inner_radius, COLLIDER_SEGMENTS));
+        }
+        let points: Vec<Vector2<f32>> = points.into_iter().map(|point| self.transform.apply(point)).collect();
+        Collider::Polygon(ConvexPolygon::new(convex_hull(&points)))
+    }
+
+    // One piece per segment, a wedge for pies and a quad for rings, so the hole of a ring and
+    // the mouth of a wide arc stay empty.
+    fn colliders(&self) -> Vec<Collider> {
+        let outer = self.arc_points(self.radius, COLLIDER_SEGMENTS);
+        let inner_radius = self.inner_radius();
+        let inner = if inner_radius <= 0.0 {
+            vec![Vector2::new(0.0, 0.0); outer.len()]
+        } else {
+            self.arc_points(inner_radius, COLLIDER_SEGMENTS)
+        };
+        (0..outer.len() - 1)
+            .map(|i| {
+                let mut corners = vec![outer[i], outer[i + 1], inner[i + 1]];
+                if inner_radius > 0.0 {
+                    corners.push(inner[i]);
+                }
+                let corners = corners.into_iter().map(|corner| self.transform.apply(corner)).collect();
+                Collider::Polygon(ConvexPolygon::new(corners))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::graphics::geometry::collision::Circle;
+
+    fn hits(arc: &KArc, x: f32, y: f32, radius: f32) -> bool {
+        let circle = Collider::Circle(Circle::new(Vector2::new(x, y), radius));
+        arc.colliders().iter().any(|collider| collider.collides_with(&circle).is_some())
+    }
+
+    #[test]
+    fn rings_have_an_empty_hole() {
+        let ring = KArc::new(0.0, 0.0, 10.0, 0.0, TAU, 2.0, [1.0; 4]);
+        assert!(!hits(&ring, 0.0, 0.0, 5.0));
+        assert!(hits(&ring, 9.0, 0.0, 0.5));
+        assert!(hits(&ring, 0.0, -9.0, 0.5));
+    }
+
+    #[test]
+    fn wide_pies_leave_their_mouth_open() {
+        let pie = KArc::new(0.0, 0.0, 10.0, 0.0, 1.5 * PI, 10.0, [1.0; 4]);
+        assert!(hits(&pie, 0.0, 0.0, 0.5));
+        assert!(hits(&pie, -5.0, -5.0, 0.5));
+        assert!(!hits(&pie, 5.0, -5.0, 0.5));
+    }
+}
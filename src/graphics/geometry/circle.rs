@@ -0,0 +1,60 @@
+use std::ops::{Deref, DerefMut};
+
+use cgmath::Vector2;
+
+use crate::graphics::renderer::Renderer2D;
+use super::{collision::{Circle, Collider}, ellipse::KEllipse, CurveMode, PhysicalObject};
+
+pub struct KCircle(KEllipse);
+
+impl KCircle {
+    pub fn new(x: f32, y: f32, radius: f32, color: [f32; 4]) -> Self {
+        Self(KEllipse::new(x, y, radius, radius, color))
+    }
+
+    pub fn with_mode(self, mode: CurveMode) -> Self {
+        Self(self.0.with_mode(mode))
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.0.radius_x
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.0.radius_x = radius;
+        self.0.radius_y = radius;
+    }
+
+    pub fn into_ellipse(self) -> KEllipse {
+        self.0
+    }
+}
+
+impl Deref for KCircle {
+    type Target = KEllipse;
+
+    fn deref(&self) -> &KEllipse {
+        &self.0
+    }
+}
+
+impl DerefMut for KCircle {
+    fn deref_mut(&mut self) -> &mut KEllipse {
+        &mut self.0
+    }
+}
+
+impl PhysicalObject for KCircle {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        self.0.draw(renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        let transform = &self.0.transform;
+        if (transform.scale.x - transform.scale.y).abs() > f32::EPSILON || self.0.radius_x != self.0.radius_y {
+            return self.0.collider();
+        }
+        let center = transform.apply(Vector2::new(0.0, 0.0));
+        Collider::Circle(Circle::new(center, self.0.radius_x * transform.scale.x.abs()))
+    }
+}
@@ -0,0 +1,505 @@
+use cgmath::{InnerSpace, Vector2};
+
+use crate::graphics::transform::Transform2D;
+use super::triangulate::{is_convex, signed_area};
+
+const EPSILON: f32 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector2<f32>,
+    pub max: Vector2<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: Vector2<f32>, max: Vector2<f32>) -> Self {
+        Aabb {
+            min: Vector2::new(min.x.min(max.x), min.y.min(max.y)),
+            max: Vector2::new(min.x.max(max.x), min.y.max(max.y)),
+        }
+    }
+
+    pub fn from_center(center: Vector2<f32>, half_extents: Vector2<f32>) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    pub fn center(&self) -> Vector2<f32> {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn vertices(&self) -> Vec<Vector2<f32>> {
+        vec![
+            self.min,
+            Vector2::new(self.max.x, self.min.y),
+            self.max,
+            Vector2::new(self.min.x, self.max.y),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Vector2<f32>,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vector2<f32>, radius: f32) -> Self {
+        Circle { center, radius }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Vector2<f32>,
+    pub end: Vector2<f32>,
+}
+
+impl Segment {
+    pub fn new(start: Vector2<f32>, end: Vector2<f32>) -> Self {
+        Segment { start, end }
+    }
+
+    pub fn closest_point(&self, point: Vector2<f32>) -> Vector2<f32> {
+        closest_point_on_segment(self.start, self.end, point)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexPolygon {
+    vertices: Vec<Vector2<f32>>,
+}
+
+impl ConvexPolygon {
+    pub fn new(mut vertices: Vec<Vector2<f32>>) -> Self {
+        if signed_area(&vertices) < 0.0 {
+            vertices.reverse();
+        }
+        ConvexPolygon { vertices }
+    }
+
+    pub fn try_new(vertices: Vec<Vector2<f32>>) -> Option<Self> {
+        if is_convex(&vertices) {
+            Some(Self::new(vertices))
+        } else {
+            None
+        }
+    }
+
+    pub fn vertices(&self) -> &[Vector2<f32>] {
+        &self.vertices
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Collider {
+    Aabb(Aabb),
+    Circle(Circle),
+    Segment(Segment),
+    Polygon(ConvexPolygon),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    pub normal: Vector2<f32>,
+    pub depth: f32,
+    pub points: Vec<Vector2<f32>>,
+}
+
+impl Contact {
+    fn flipped(mut self) -> Self {
+        self.normal = -self.normal;
+        self
+    }
+}
+
+impl Collider {
+    pub fn from_rect(transform: &Transform2D, half_width: f32, half_height: f32) -> Collider {
+        let corners: Vec<Vector2<f32>> = [
+            Vector2::new(-half_width, -half_height),
+            Vector2::new(half_width, -half_height),
+            Vector2::new(half_width, half_height),
+            Vector2::new(-half_width, half_height),
+        ]
+        .iter()
+        .map(|corner| transform.apply(*corner))
+        .collect();
+
+        if transform.is_axis_aligned() {
+            let min = corners.iter().fold(corners[0], |min, c| Vector2::new(min.x.min(c.x), min.y.min(c.y)));
+            let max = corners.iter().fold(corners[0], |max, c| Vector2::new(max.x.max(c.x), max.y.max(c.y)));
+            Collider::Aabb(Aabb::new(min, max))
+        } else {
+            Collider::Polygon(ConvexPolygon::new(corners))
+        }
+    }
+
+    pub fn collides_with(&self, other: &Collider) -> Option<Contact> {
+        match (self.as_circle(), other.as_circle()) {
+            (Some(a), Some(b)) => circle_circle(a, b),
+            (Some(a), None) => polygon_circle(&other.polygon_vertices(), a).map(Contact::flipped),
+            (None, Some(b)) => polygon_circle(&self.polygon_vertices(), b),
+            (None, None) => polygon_polygon(&self.polygon_vertices(), &other.polygon_vertices()),
+        }
+    }
+
+    fn as_circle(&self) -> Option<&Circle> {
+        match self {
+            Collider::Circle(circle) => Some(circle),
+            _ => None,
+        }
+    }
+
+    fn polygon_vertices(&self) -> Vec<Vector2<f32>> {
+        match self {
+            Collider::Aabb(aabb) => aabb.vertices(),
+            Collider::Segment(segment) => vec![segment.start, segment.end],
+            Collider::Polygon(polygon) => polygon.vertices.clone(),
+            Collider::Circle(circle) => vec![circle.center],
+        }
+    }
+}
+
+pub fn convex_hull(points: &[Vector2<f32>]) -> Vec<Vector2<f32>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let cross = |o: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+    let mut hull: Vec<Vector2<f32>> = Vec::with_capacity(sorted.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &Vector2<f32>>> = if pass == 0 {
+            Box::new(sorted.iter())
+        } else {
+            Box::new(sorted.iter().rev())
+        };
+        for &point in iter {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+fn perp(v: Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(v.y, -v.x)
+}
+
+fn closest_point_on_segment(a: Vector2<f32>, b: Vector2<f32>, point: Vector2<f32>) -> Vector2<f32> {
+    let ab = b - a;
+    let length_squared = ab.magnitude2();
+    if length_squared < EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+fn edge_normals(vertices: &[Vector2<f32>]) -> Vec<Vector2<f32>> {
+    let mut normals = Vec::with_capacity(vertices.len());
+    for i in 0..vertices.len() {
+        let edge = vertices[(i + 1) % vertices.len()] - vertices[i];
+        if edge.magnitude2() > EPSILON {
+            normals.push(perp(edge).normalize());
+        }
+    }
+    normals
+}
+
+fn project(vertices: &[Vector2<f32>], axis: Vector2<f32>) -> (f32, f32) {
+    vertices.iter().fold((f32::MAX, f32::MIN), |(min, max), vertex| {
+        let projection = vertex.dot(axis);
+        (min.min(projection), max.max(projection))
+    })
+}
+
+fn contains_point(vertices: &[Vector2<f32>], point: Vector2<f32>) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+    (0..vertices.len()).all(|i| {
+        let a = vertices[i];
+        let b = vertices[(i + 1) % vertices.len()];
+        perp(b - a).dot(point - a) <= 0.0
+    })
+}
+
+fn circle_circle(a: &Circle, b: &Circle) -> Option<Contact> {
+    let delta = b.center - a.center;
+    let distance = delta.magnitude();
+    let radii = a.radius + b.radius;
+    if distance >= radii {
+        return None;
+    }
+
+    let normal = if distance > EPSILON { delta / distance } else { Vector2::new(1.0, 0.0) };
+    Some(Contact {
+        normal,
+        depth: radii - distance,
+        points: vec![a.center + normal * a.radius],
+    })
+}
+
+fn polygon_circle(vertices: &[Vector2<f32>], circle: &Circle) -> Option<Contact> {
+    let mut closest = *vertices.first()?;
+    let mut closest_distance = f32::MAX;
+    for i in 0..vertices.len() {
+        let point = closest_point_on_segment(vertices[i], vertices[(i + 1) % vertices.len()], circle.center);
+        let distance = (circle.center - point).magnitude2();
+        if distance < closest_distance {
+            closest = point;
+            closest_distance = distance;
+        }
+    }
+
+    let closest_distance = closest_distance.sqrt();
+    let inside = contains_point(vertices, circle.center);
+    if !inside && closest_distance >= circle.radius {
+        return None;
+    }
+
+    let normal = if closest_distance > EPSILON {
+        let outward = (circle.center - closest) / closest_distance;
+        if inside { -outward } else { outward }
+    } else {
+        edge_normals(vertices)
+            .into_iter()
+            .next()
+            .unwrap_or(Vector2::new(1.0, 0.0))
+    };
+    let depth = if inside { circle.radius + closest_distance } else { circle.radius - closest_distance };
+
+    Some(Contact {
+        normal,
+        depth,
+        points: vec![closest],
+    })
+}
+
+fn polygon_polygon(a: &[Vector2<f32>], b: &[Vector2<f32>]) -> Option<Contact> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    // Points and zero-length edges have no axes to separate along; treat them as not touching.
+    let axes: Vec<Vector2<f32>> = edge_normals(a).into_iter().chain(edge_normals(b)).collect();
+    if axes.is_empty() {
+        return None;
+    }
+    let mut best_depth = f32::MAX;
+    let mut best_normal = Vector2::new(1.0, 0.0);
+
+    for axis in axes {
+        let (min_a, max_a) = project(a, axis);
+        let (min_b, max_b) = project(b, axis);
+        let forward = max_a - min_b;
+        let backward = max_b - min_a;
+        if forward <= 0.0 || backward <= 0.0 {
+            return None;
+        }
+        if forward < best_depth {
+            best_depth = forward;
+            best_normal = axis;
+        }
+        if backward < best_depth {
+            best_depth = backward;
+            best_normal = -axis;
+        }
+    }
+
+    Some(Contact {
+        normal: best_normal,
+        depth: best_depth,
+        points: contact_points(a, b, best_normal),
+    })
+}
+
+fn best_edge(vertices: &[Vector2<f32>], normal: Vector2<f32>) -> (Vector2<f32>, Vector2<f32>, Vector2<f32>) {
+    let count = vertices.len();
+    let index = (0..count)
+        .max_by(|&i, &j| vertices[i].dot(normal).total_cmp(&vertices[j].dot(normal)))
+        .unwrap_or(0);
+
+    let vertex = vertices[index];
+    if count < 2 {
+        return (vertex, vertex, vertex);
+    }
+    let next = vertices[(index + 1) % count];
+    let previous = vertices[(index + count - 1) % count];
+
+    let left = (vertex - next).normalize();
+    let right = (vertex - previous).normalize();
+    if right.dot(normal) <= left.dot(normal) {
+        (vertex, previous, vertex)
+    } else {
+        (vertex, vertex, next)
+    }
+}
+
+fn clip(v1: Vector2<f32>, v2: Vector2<f32>, direction: Vector2<f32>, offset: f32) -> Vec<Vector2<f32>> {
+    let mut points = Vec::with_capacity(2);
+    let d1 = direction.dot(v1) - offset;
+    let d2 = direction.dot(v2) - offset;
+    if d1 >= 0.0 {
+        points.push(v1);
+    }
+    if d2 >= 0.0 {
+        points.push(v2);
+    }
+    if d1 * d2 < 0.0 {
+        let t = d1 / (d1 - d2);
+        points.push(v1 + (v2 - v1) * t);
+    }
+    points
+}
+
+fn contact_points(a: &[Vector2<f32>], b: &[Vector2<f32>], normal: Vector2<f32>) -> Vec<Vector2<f32>> {
+    let edge_a = best_edge(a, normal);
+    let edge_b = best_edge(b, -normal);
+
+    let length_a = (edge_a.2 - edge_a.1).magnitude();
+    let length_b = (edge_b.2 - edge_b.1).magnitude();
+    if length_a < EPSILON || length_b < EPSILON {
+        return vec![if length_a < EPSILON { edge_a.0 } else { edge_b.0 }];
+    }
+
+    let perpendicular_a = ((edge_a.2 - edge_a.1) / length_a).dot(normal).abs();
+    let perpendicular_b = ((edge_b.2 - edge_b.1) / length_b).dot(normal).abs();
+    let (reference, incident, flip) = if perpendicular_a <= perpendicular_b {
+        (edge_a, edge_b, false)
+    } else {
+        (edge_b, edge_a, true)
+    };
+
+    let direction = (reference.2 - reference.1).normalize();
+    let start = direction.dot(reference.1);
+    let points = clip(incident.1, incident.2, direction, start);
+    if points.len() < 2 {
+        return vec![incident.0];
+    }
+    let end = direction.dot(reference.2);
+    let points = clip(points[0], points[1], -direction, -end);
+    if points.len() < 2 {
+        return vec![incident.0];
+    }
+
+    let mut reference_normal = perp(direction);
+    if reference_normal.dot(normal) < 0.0 {
+        reference_normal = -reference_normal;
+    }
+    if flip {
+        reference_normal = -reference_normal;
+    }
+    let max = reference_normal.dot(reference.0);
+
+    let contacts: Vec<Vector2<f32>> = points
+        .into_iter()
+        .filter(|point| reference_normal.dot(*point) <= max + EPSILON)
+        .collect();
+    if contacts.is_empty() {
+        vec![incident.0]
+    } else {
+        contacts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Collider {
+        Collider::Aabb(Aabb::new(Vector2::new(min_x, min_y), Vector2::new(max_x, max_y)))
+    }
+
+    fn circle(x: f32, y: f32, radius: f32) -> Collider {
+        Collider::Circle(Circle::new(Vector2::new(x, y), radius))
+    }
+
+    fn assert_close(actual: Vector2<f32>, expected: Vector2<f32>) {
+        assert!((actual - expected).magnitude() < 1e-4, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn circle_circle_normal_points_from_first_to_second() {
+        let contact = circle(0.0, 0.0, 1.0).collides_with(&circle(1.5, 0.0, 1.0)).unwrap();
+        assert_close(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert!(circle(0.0, 0.0, 1.0).collides_with(&circle(3.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn aabb_aabb_uses_the_axis_of_least_penetration() {
+        let contact = aabb(0.0, 0.0, 2.0, 2.0).collides_with(&aabb(1.5, 0.5, 3.5, 1.5)).unwrap();
+        assert_close(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert_eq!(contact.points.len(), 2);
+
+        let reversed = aabb(1.5, 0.5, 3.5, 1.5).collides_with(&aabb(0.0, 0.0, 2.0, 2.0)).unwrap();
+        assert_close(reversed.normal, Vector2::new(-1.0, 0.0));
+        assert!(aabb(0.0, 0.0, 1.0, 1.0).collides_with(&aabb(1.0, 0.0, 2.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn polygon_circle_normal_direction_is_flipped_with_argument_order() {
+        let contact = aabb(0.0, 0.0, 2.0, 2.0).collides_with(&circle(2.5, 1.0, 1.0)).unwrap();
+        assert_close(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert_close(contact.points[0], Vector2::new(2.0, 1.0));
+
+        let reversed = circle(2.5, 1.0, 1.0).collides_with(&aabb(0.0, 0.0, 2.0, 2.0)).unwrap();
+        assert_close(reversed.normal, Vector2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn circle_inside_polygon_is_pushed_out_through_the_nearest_edge() {
+        let contact = aabb(0.0, 0.0, 4.0, 4.0).collides_with(&circle(3.5, 2.0, 1.0)).unwrap();
+        assert_close(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.depth - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotated_polygon_against_aabb() {
+        let diamond = Collider::Polygon(ConvexPolygon::new(vec![
+            Vector2::new(0.0, -1.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(-1.0, 0.0),
+        ]));
+        let contact = diamond.collides_with(&aabb(0.8, -1.0, 2.8, 1.0)).unwrap();
+        assert_close(contact.normal, Vector2::new(1.0, 0.0));
+        assert!((contact.depth - 0.2).abs() < 1e-5);
+        assert!(diamond.collides_with(&aabb(0.8, 0.8, 2.0, 2.0)).is_none());
+    }
+
+    #[test]
+    fn segment_against_circle() {
+        let segment = Collider::Segment(Segment::new(Vector2::new(-2.0, 0.0), Vector2::new(2.0, 0.0)));
+        let contact = segment.collides_with(&circle(0.0, 0.5, 1.0)).unwrap();
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert!(contact.normal.y.abs() > 0.99);
+        assert!(segment.collides_with(&circle(0.0, 1.5, 1.0)).is_none());
+    }
+
+    #[test]
+    fn empty_polygons_never_collide() {
+        let empty = Collider::Polygon(ConvexPolygon::new(Vec::new()));
+        assert!(empty.collides_with(&circle(0.0, 0.0, 1.0)).is_none());
+        assert!(circle(0.0, 0.0, 1.0).collides_with(&empty).is_none());
+        assert!(empty.collides_with(&aabb(-1.0, -1.0, 1.0, 1.0)).is_none());
+        assert!(aabb(-1.0, -1.0, 1.0, 1.0).collides_with(&empty).is_none());
+    }
+
+    #[test]
+    fn distant_zero_length_segments_do_not_collide() {
+        let point = |x: f32, y: f32| Collider::Segment(Segment::new(Vector2::new(x, y), Vector2::new(x, y)));
+        assert!(point(0.0, 0.0).collides_with(&point(100.0, 50.0)).is_none());
+        assert!(point(0.0, 0.0).collides_with(&aabb(10.0, 10.0, 11.0, 11.0)).is_none());
+        assert!(point(10.5, 10.5).collides_with(&aabb(10.0, 10.0, 11.0, 11.0)).is_some());
+    }
+}
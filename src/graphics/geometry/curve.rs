@@ -0,0 +1,300 @@
+use cgmath::{InnerSpace, Vector2};
+
+use crate::graphics::{renderer::Renderer2D, transform::Transform2D};
+use super::{collision::Collider, polyline::{KPolyline, LineCap, LineJoin}, PhysicalObject};
+
+const MAX_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Quadratic([Vector2<f32>; 3]),
+    Cubic([Vector2<f32>; 4]),
+    CatmullRom { points: Vec<Vector2<f32>>, closed: bool },
+}
+
+impl Curve {
+    pub fn quadratic(start: (f32, f32), control: (f32, f32), end: (f32, f32)) -> Self {
+        Curve::Quadratic([start.into(), control.into(), end.into()])
+    }
+
+    pub fn cubic(start: (f32, f32), control1: (f32, f32), control2: (f32, f32), end: (f32, f32)) -> Self {
+        Curve::Cubic([start.into(), control1.into(), control2.into(), end.into()])
+    }
+
+    pub fn catmull_rom(points: &[(f32, f32)], closed: bool) -> Self {
+        Curve::CatmullRom {
+            points: points.iter().map(|&point| point.into()).collect(),
+            closed,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Curve::CatmullRom { points, closed: true } if points.len() > 2)
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2<f32> {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Quadratic([p0, p1, p2]) => {
+                let u = 1.0 - t;
+                p0 * (u * u) + p1 * (2.0 * u * t) + p2 * (t * t)
+            }
+            Curve::Cubic(points) => cubic_point(points, t),
+            Curve::CatmullRom { .. } => match self.spline_segment(t) {
+                Some((segment, local)) => cubic_point(&segment, local),
+                None => self.first_point(),
+            },
+        }
+    }
+
+    pub fn derivative_at(&self, t: f32) -> Vector2<f32> {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Quadratic([p0, p1, p2]) => (p1 - p0) * (2.0 * (1.0 - t)) + (p2 - p1) * (2.0 * t),
+            Curve::Cubic(points) => cubic_derivative(points, t),
+            Curve::CatmullRom { .. } => match self.spline_segment(t) {
+                Some((segment, local)) => cubic_derivative(&segment, local) * self.segment_count() as f32,
+                None => Vector2::new(0.0, 0.0),
+            },
+        }
+    }
+
+    pub fn tangent_at(&self, t: f32) -> Vector2<f32> {
+        let derivative = self.derivative_at(t);
+        if derivative.magnitude2() > f32::EPSILON {
+            return derivative.normalize();
+        }
+        let step = 1e-3;
+        let chord = self.point_at((t + step).min(1.0)) - self.point_at((t - step).max(0.0));
+        if chord.magnitude2() > f32::EPSILON { chord.normalize() } else { Vector2::new(1.0, 0.0) }
+    }
+
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vector2<f32>> {
+        self.flatten_with_parameters(tolerance).into_iter().map(|(_, point)| point).collect()
+    }
+
+    pub fn flatten_with_parameters(&self, tolerance: f32) -> Vec<(f32, Vector2<f32>)> {
+        let tolerance = tolerance.max(1e-5);
+        let segments = self.bezier_segments();
+        let mut output = Vec::new();
+        if let Some(first) = segments.first() {
+            output.push((0.0, first[0]));
+        } else {
+            output.push((0.0, self.first_point()));
+        }
+
+        let span = 1.0 / segments.len().max(1) as f32;
+        for (index, segment) in segments.iter().enumerate() {
+            let start = index as f32 * span;
+            subdivide(segment, start, start + span, tolerance, 0, &mut output);
+        }
+        output
+    }
+
+    pub fn arc_length_table(&self, tolerance: f32) -> ArcLengthTable {
+        let samples = self.flatten_with_parameters(tolerance);
+        let mut entries = Vec::with_capacity(samples.len());
+        let mut distance = 0.0;
+        for (index, &(t, point)) in samples.iter().enumerate() {
+            if index > 0 {
+                distance += (point - samples[index - 1].1).magnitude();
+            }
+            entries.push((distance, t));
+        }
+        ArcLengthTable { entries }
+    }
+
+    fn first_point(&self) -> Vector2<f32> {
+        match self {
+            Curve::Quadratic(points) => points[0],
+            Curve::Cubic(points) => points[0],
+            Curve::CatmullRom { points, .. } => points.first().copied().unwrap_or(Vector2::new(0.0, 0.0)),
+        }
+    }
+
+    fn segment_count(&self) -> usize {
+        match self {
+            Curve::CatmullRom { points, .. } if self.is_closed() => points.len(),
+            Curve::CatmullRom { points, .. } => points.len().saturating_sub(1),
+            _ => 1,
+        }
+    }
+
+    fn spline_segment(&self, t: f32) -> Option<([Vector2<f32>; 4], f32)> {
+        let count = self.segment_count();
+        if count == 0 {
+            return None;
+        }
+        let scaled = t * count as f32;
+        let index = (scaled.floor() as usize).min(count - 1);
+        Some((self.catmull_rom_segment(index), scaled - index as f32))
+    }
+
+    fn catmull_rom_segment(&self, index: usize) -> [Vector2<f32>; 4] {
+        let Curve::CatmullRom { points, .. } = self else {
+            unreachable!("catmull_rom_segment on a Bezier curve")
+        };
+        let count = points.len();
+        let at = |offset: isize| -> Vector2<f32> {
+            let position = index as isize + offset;
+            if self.is_closed() {
+                points[position.rem_euclid(count as isize) as usize]
+            } else if position < 0 {
+                points[0] * 2.0 - points[1]
+            } else if position as usize >= count {
+                points[count - 1] * 2.0 - points[count - 2]
+            } else {
+                points[position as usize]
+            }
+        };
+        let (p0, p1, p2, p3) = (at(-1), at(0), at(1), at(2));
+        [p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2]
+    }
+
+    fn bezier_segments(&self) -> Vec<[Vector2<f32>; 4]> {
+        match self {
+            Curve::Quadratic([p0, p1, p2]) => {
+                vec![[*p0, p0 + (p1 - p0) * (2.0 / 3.0), p2 + (p1 - p2) * (2.0 / 3.0), *p2]]
+            }
+            Curve::Cubic(points) => vec![*points],
+            Curve::CatmullRom { .. } => (0..self.segment_count()).map(|index| self.catmull_rom_segment(index)).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthTable {
+    entries: Vec<(f32, f32)>,
+}
+
+impl ArcLengthTable {
+    pub fn length(&self) -> f32 {
+        self.entries.last().map(|&(distance, _)| distance).unwrap_or(0.0)
+    }
+
+    pub fn parameter_at(&self, distance: f32) -> f32 {
+        let distance = distance.clamp(0.0, self.length());
+        let upper = self.entries.partition_point(|&(entry, _)| entry < distance);
+        if upper == 0 {
+            return self.entries.first().map(|&(_, t)| t).unwrap_or(0.0);
+        }
+        if upper >= self.entries.len() {
+            return 1.0;
+        }
+        let (d0, t0) = self.entries[upper - 1];
+        let (d1, t1) = self.entries[upper];
+        if d1 - d0 <= f32::EPSILON {
+            return t1;
+        }
+        t0 + (t1 - t0) * (distance - d0) / (d1 - d0)
+    }
+
+    pub fn parameter_at_fraction(&self, fraction: f32) -> f32 {
+        self.parameter_at(fraction * self.length())
+    }
+}
+
+fn cubic_point(points: &[Vector2<f32>; 4], t: f32) -> Vector2<f32> {
+    let u = 1.0 - t;
+    points[0] * (u * u * u) + points[1] * (3.0 * u * u * t) + points[2] * (3.0 * u * t * t) + points[3] * (t * t * t)
+}
+
+fn cubic_derivative(points: &[Vector2<f32>; 4], t: f32) -> Vector2<f32> {
+    let u = 1.0 - t;
+    (points[1] - points[0]) * (3.0 * u * u) + (points[2] - points[1]) * (6.0 * u * t) + (points[3] - points[2]) * (3.0 * t * t)
+}
+
+fn distance_to_line(point: Vector2<f32>, start: Vector2<f32>, end: Vector2<f32>) -> f32 {
+    let chord = end - start;
+    let length = chord.magnitude();
+    if length <= f32::EPSILON {
+        return (point - start).magnitude();
+    }
+    ((point - start).x * chord.y - (point - start).y * chord.x).abs() / length
+}
+
+fn subdivide(points: &[Vector2<f32>; 4], t0: f32, t1: f32, tolerance: f32, depth: u32, output: &mut Vec<(f32, Vector2<f32>)>) {
+    let flat = distance_to_line(points[1], points[0], points[3]) <= tolerance
+        && distance_to_line(points[2], points[0], points[3]) <= tolerance;
+    if flat || depth >= MAX_DEPTH {
+        output.push((t1, points[3]));
+        return;
+    }
+
+    let p01 = (points[0] + points[1]) * 0.5;
+    let p12 = (points[1] + points[2]) * 0.5;
+    let p23 = (points[2] + points[3]) * 0.5;
+    let p012 = (p01 + p12) * 0.5;
+    let p123 = (p12 + p23) * 0.5;
+    let middle = (p012 + p123) * 0.5;
+    let t_middle = (t0 + t1) * 0.5;
+    subdivide(&[points[0], p01, p012, middle], t0, t_middle, tolerance, depth + 1, output);
+    subdivide(&[middle, p123, p23, points[3]], t_middle, t1, tolerance, depth + 1, output);
+}
+
+pub struct KCurve {
+    pub curve: Curve,
+    pub transform: Transform2D,
+    pub width: f32,
+    pub color: [f32; 4],
+    pub tolerance: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+}
+
+impl KCurve {
+    pub fn new(curve: Curve, width: f32, color: [f32; 4]) -> Self {
+        Self {
+            curve,
+            transform: Transform2D::default(),
+            width,
+            color,
+            tolerance: (width * 0.1).max(1e-4),
+            join: LineJoin::Round,
+            cap: LineCap::Butt,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn to_polyline(&self) -> KPolyline {
+        let mut polyline = KPolyline::new(&[], self.width, self.color)
+            .with_join(self.join)
+            .with_cap(self.cap)
+            .closed(self.curve.is_closed());
+        polyline.points = self.curve.flatten(self.tolerance);
+        polyline.transform = self.transform;
+        polyline
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        self.to_polyline().draw(renderer);
+    }
+}
+
+impl PhysicalObject for KCurve {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KCurve::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        self.to_polyline().collider()
+    }
+
+    fn colliders(&self) -> Vec<Collider> {
+        self.to_polyline().colliders()
+    }
+}
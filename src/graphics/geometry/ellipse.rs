@@ -0,0 +1,100 @@
+use std::f32::consts::TAU;
+
+use cgmath::Vector2;
+
+use crate::graphics::{renderer::{Material, Primitive, Renderer2D, Vertex2D}, transform::Transform2D};
+use super::{collision::{Collider, ConvexPolygon}, CurveMode, PhysicalObject};
+
+const COLLIDER_SEGMENTS: u32 = 32;
+
+pub struct KEllipse {
+    pub transform: Transform2D,
+    pub radius_x: f32,
+    pub radius_y: f32,
+    pub color: [f32; 4],
+    pub mode: CurveMode,
+}
+
+impl KEllipse {
+    pub fn new(x: f32, y: f32, radius_x: f32, radius_y: f32, color: [f32; 4]) -> Self {
+        Self {
+            transform: Transform2D::new(x, y),
+            radius_x,
+            radius_y,
+            color,
+            mode: CurveMode::default(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: CurveMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        match self.mode {
+            CurveMode::Segments(segments) => {
+                let segments = segments.max(3);
+                let mut vertices = Vec::with_capacity(segments as usize + 1);
+                vertices.push(Vertex2D::colored(0.0, 0.0, self.color));
+                for point in ellipse_points(self.radius_x, self.radius_y, segments) {
+                    vertices.push(Vertex2D::colored(point.x, point.y, self.color));
+                }
+
+                let mut indices = Vec::with_capacity(segments as usize * 3);
+                for i in 0..segments {
+                    indices.extend_from_slice(&[0, i + 1, (i + 1) % segments + 1]);
+                }
+                renderer.submit_transformed(Primitive::Triangles, Material::Color, &vertices, &indices, &self.transform);
+            }
+            CurveMode::Sdf => {
+                submit_sdf_quad(renderer, self.radius_x, self.radius_y, self.color, [0.0; 4], &self.transform);
+            }
+        }
+    }
+}
+
+pub(crate) fn ellipse_points(radius_x: f32, radius_y: f32, segments: u32) -> Vec<Vector2<f32>> {
+    (0..segments)
+        .map(|i| {
+            let angle = TAU * i as f32 / segments as f32;
+            Vector2::new(radius_x * angle.cos(), radius_y * angle.sin())
+        })
+        .collect()
+}
+
+pub(crate) fn submit_sdf_quad(
+    renderer: &mut Renderer2D,
+    radius_x: f32,
+    radius_y: f32,
+    color: [f32; 4],
+    params: [f32; 4],
+    transform: &Transform2D,
+) {
+    let corner = |x: f32, y: f32| {
+        let mut vertex = Vertex2D::colored(x * radius_x, y * radius_y, color);
+        vertex.tex_coords = [x, y];
+        vertex.params = params;
+        vertex
+    };
+    renderer.submit_quad_transformed(Material::Sdf, [
+        corner(1.0, 1.0),
+        corner(1.0, -1.0),
+        corner(-1.0, -1.0),
+        corner(-1.0, 1.0),
+    ], transform);
+}
+
+impl PhysicalObject for KEllipse {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KEllipse::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        let points = ellipse_points(self.radius_x, self.radius_y, COLLIDER_SEGMENTS)
+            .into_iter()
+            .map(|point| self.transform.apply(point))
+            .collect();
+        Collider::Polygon(ConvexPolygon::new(points))
+    }
+}
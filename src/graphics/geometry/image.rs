@@ -1,147 +1,76 @@
-use gl::types::{GLfloat, GLsizei};
-use crate::graphics::gl_wrapper::{BufferObject, ShaderProgram, Vao, VertexAttribute};
-use image::GenericImageView;
+use std::rc::Rc;
+
+use crate::error::{Error, Result};
+use crate::graphics::{gl_wrapper::{Texture2D, TextureOptions}, renderer::{Material, Renderer2D, Vertex2D}, transform::Transform2D};
+
+use super::{collision::Collider, PhysicalObject};
 
 pub struct KImage {
-    pub x: f32,
-    pub y: f32,
+    pub transform: Transform2D,
     pub width: f32,
     pub height: f32,
-    pub texture_id: u32,
-    vao: Vao,
-    vbo: BufferObject,
-    ibo: BufferObject,
-    shader_program: ShaderProgram,
+    pub texture: Rc<Texture2D>,
 }
 
 impl KImage {
-    pub fn new(x: f32, y: f32, width: f32, height: f32, image_path: &str) -> Self {
-        let img = image::open(image_path).expect("Error on loading image");
-        img.flipv();
-        let (img_width, img_height) = img.dimensions();
-        let img_data = img.to_rgba8();
-        let img_data = img_data.into_raw();
-
-        let mut texture_id: u32 = 0;
-        unsafe {
-            gl::GenTextures(1, &mut texture_id);
-            gl::BindTexture(gl::TEXTURE_2D, texture_id);
-            gl::TexImage2D(
-                gl::TEXTURE_2D,
-                0,
-                gl::RGBA as i32,
-                img_width as i32,
-                img_height as i32,
-                0,
-                gl::RGBA,
-                gl::UNSIGNED_BYTE,
-                img_data.as_ptr() as *const std::ffi::c_void,
-            );
-            gl::GenerateMipmap(gl::TEXTURE_2D);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-        }
-
-        let vertices: [f32; 20] = [
-            x + width / 2.0, y + height / 2.0, 0.0, 1.0, 0.0,
-            x + width / 2.0, y - height / 2.0, 0.0, 1.0, 1.0,
-            x - width / 2.0, y - height / 2.0, 0.0, 0.0, 1.0,
-            x - width / 2.0, y + height / 2.0, 0.0, 0.0, 0.0, 
-        ];
-        
-        let indices = [0, 1, 3, 1, 2, 3];
-
-        let vao = Vao::new();
-        vao.bind();
-
-        let vbo = BufferObject::new(gl::ARRAY_BUFFER, gl::STATIC_DRAW);
-        vbo.bind();
-        vbo.store_f32data(&vertices);
-
-        let ibo = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, gl::STATIC_DRAW);
-        ibo.bind();
-        ibo.store_i32data(&indices);
-
-        let vertex_shader_source = r#"
-            #version 330 core
-            layout(location = 0) in vec3 aPos;
-            layout(location = 1) in vec2 aTexCoord;
-            out vec2 TexCoord;
-            void main() {
-                gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
-                TexCoord = aTexCoord;
-            }
-        "#;
-
-        let fragment_shader_source = r#"
-            #version 330 core
-            in vec2 TexCoord;
-            out vec4 FragColor;
-            uniform sampler2D texture1;
-            void main() {
-                FragColor = texture(texture1, TexCoord);
-            }
-        "#;
-
-        let shader_program = ShaderProgram::new(vertex_shader_source, fragment_shader_source);
-        shader_program.bind();
-
-        let position_attribute = VertexAttribute::new(
-            0,
-            3,
-            gl::FLOAT,
-            gl::FALSE,
-            5 * std::mem::size_of::<GLfloat>() as GLsizei,
-            std::ptr::null(),
-        );
-        position_attribute.enable();
-
-        let texcoord_attribute = VertexAttribute::new(
-            1,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            5 * std::mem::size_of::<GLfloat>() as GLsizei,
-            (3 * std::mem::size_of::<GLfloat>()) as *const _,
-        );
-        texcoord_attribute.enable();
-
-        vao.unbind();
-        vbo.unbind();
-        ibo.unbind();
+    pub fn new(x: f32, y: f32, width: f32, height: f32, image_path: &str) -> Result<Self> {
+        let img = image::open(image_path).map_err(|source| Error::ImageLoad {
+            path: image_path.to_string(),
+            source,
+        })?;
+        let texture = Texture2D::from_image(&img, TextureOptions::nearest());
+        Ok(Self::from_texture(x, y, width, height, Rc::new(texture)))
+    }
 
+    pub fn from_texture(x: f32, y: f32, width: f32, height: f32, texture: Rc<Texture2D>) -> Self {
         Self {
-            x,
-            y,
+            transform: Transform2D::new(x, y),
             width,
             height,
-            texture_id,
-            vao,
-            vbo,
-            ibo,
-            shader_program,
+            texture,
         }
     }
 
-    pub fn draw(&mut self) {
-        self.shader_program.bind();
-        unsafe {
-            gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
-            gl::GenerateMipmap(gl::TEXTURE_2D);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-        
-        }
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        submit_textured_quad(renderer, &self.texture, self.width, self.height, [0.0, 0.0, 1.0, 1.0], &self.transform);
+    }
+}
 
-        self.vao.bind();
-        unsafe {
-            gl::DrawElements(
-                gl::TRIANGLES,
-                6,
-                gl::UNSIGNED_INT,
-                std::ptr::null(),
-            );
-        }
-        self.vao.unbind();
+// `uv` is [left, top, right, bottom] in texture space, with v = 0 at the top row of the image.
+pub(crate) fn submit_textured_quad(
+    renderer: &mut Renderer2D,
+    texture: &Texture2D,
+    width: f32,
+    height: f32,
+    uv: [f32; 4],
+    transform: &Transform2D,
+) {
+    let half_width = width / 2.0;
+    let half_height = height / 2.0;
+    let corners = textured_quad_corners([-half_width, -half_height, half_width, half_height], uv, renderer.is_y_down());
+    renderer.submit_quad_transformed(Material::Texture(texture.id()), corners, transform);
+}
+
+// `bounds` is [left, top, right, bottom] with top/bottom measured downwards on screen, so the
+// same layout works for both y-up and y-down coordinate systems.
+pub(crate) fn textured_quad_corners(bounds: [f32; 4], uv: [f32; 4], y_down: bool) -> [Vertex2D; 4] {
+    let [x0, y0, x1, y1] = bounds;
+    let [left, top, right, bottom] = uv;
+    let (top_y, bottom_y) = if y_down { (y0, y1) } else { (-y0, -y1) };
+    [
+        Vertex2D::textured(x1, top_y, right, top),
+        Vertex2D::textured(x1, bottom_y, right, bottom),
+        Vertex2D::textured(x0, bottom_y, left, bottom),
+        Vertex2D::textured(x0, top_y, left, top),
+    ]
+}
+
+impl PhysicalObject for KImage {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KImage::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        Collider::from_rect(&self.transform, self.width / 2.0, self.height / 2.0)
     }
 }
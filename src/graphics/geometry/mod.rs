@@ -1,8 +1,51 @@
+use crate::graphics::renderer::Renderer2D;
+
+use self::collision::{Collider, Contact};
+
 pub mod square;
+pub mod rect;
 pub mod line;
 pub mod image;
+pub mod collision;
+pub mod ellipse;
+pub mod circle;
+pub mod arc;
+pub mod triangulate;
+pub mod polygon;
+pub mod triangle;
+pub mod polyline;
+pub mod curve;
+pub mod nine_slice;
 
 pub trait PhysicalObject {
-    fn draw(&self);
-    fn is_colliding(&self);
-}
\ No newline at end of file
+    fn draw(&self, renderer: &mut Renderer2D);
+    // A single convex collider; for concave shapes this is only their convex hull.
+    fn collider(&self) -> Collider;
+
+    // The pieces used for collision tests. Concave shapes override this to return several
+    // convex pieces so their interior stays empty.
+    fn colliders(&self) -> Vec<Collider> {
+        vec![self.collider()]
+    }
+
+    // The deepest contact between any piece of `self` and any piece of `other`.
+    fn collides_with(&self, other: &dyn PhysicalObject) -> Option<Contact> {
+        let others = other.colliders();
+        self.colliders()
+            .iter()
+            .flat_map(|collider| others.iter().filter_map(move |other| collider.collides_with(other)))
+            .max_by(|a, b| a.depth.total_cmp(&b.depth))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveMode {
+    Segments(u32),
+    Sdf,
+}
+
+impl Default for CurveMode {
+    fn default() -> Self {
+        CurveMode::Segments(48)
+    }
+}
@@ -0,0 +1,210 @@
+use std::rc::Rc;
+
+use crate::graphics::{
+    gl_wrapper::Texture2D,
+    renderer::{Material, Primitive, Renderer2D, Vertex2D, QUAD_INDICES},
+    sprite::TextureRegion,
+    transform::Transform2D,
+};
+use super::{collision::Collider, image::textured_quad_corners, PhysicalObject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SliceMode {
+    #[default]
+    Stretch,
+    Tile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Insets {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl Insets {
+    pub fn new(left: u32, right: u32, top: u32, bottom: u32) -> Self {
+        Self { left, right, top, bottom }
+    }
+
+    pub fn uniform(inset: u32) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+}
+
+pub struct KNineSlice {
+    pub transform: Transform2D,
+    pub width: f32,
+    pub height: f32,
+    pub texture: Rc<Texture2D>,
+    pub region: TextureRegion,
+    pub insets: Insets,
+    pub border_scale: f32,
+    pub edge_mode: SliceMode,
+    pub center_mode: SliceMode,
+}
+
+#[derive(Clone, Copy)]
+struct Span {
+    start: f32,
+    end: f32,
+    uv_start: f32,
+    uv_end: f32,
+}
+
+impl KNineSlice {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, texture: Rc<Texture2D>, insets: Insets) -> Self {
+        let region = TextureRegion::full(&texture);
+        Self {
+            transform: Transform2D::new(x, y),
+            width,
+            height,
+            texture,
+            region,
+            insets,
+            border_scale: 1.0,
+            edge_mode: SliceMode::Stretch,
+            center_mode: SliceMode::Stretch,
+        }
+    }
+
+    pub fn with_region(mut self, region: TextureRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub fn with_border_scale(mut self, border_scale: f32) -> Self {
+        self.border_scale = border_scale;
+        self
+    }
+
+    pub fn with_modes(mut self, edge_mode: SliceMode, center_mode: SliceMode) -> Self {
+        self.edge_mode = edge_mode;
+        self.center_mode = center_mode;
+        self
+    }
+
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    // Splits one axis into its three bands; borders shrink proportionally when the panel is
+    // smaller than both insets together.
+    fn bands(&self, length: f32, near: u32, far: u32, origin: u32, extent: u32, texture_extent: u32) -> [Span; 3] {
+        let texture_extent = texture_extent.max(1) as f32;
+        let mut near_size = near as f32 * self.border_scale;
+        let mut far_size = far as f32 * self.border_scale;
+        if near_size + far_size > length && near_size + far_size > 0.0 {
+            let shrink = length / (near_size + far_size);
+            near_size *= shrink;
+            far_size *= shrink;
+        }
+
+        let start = -length / 2.0;
+        let end = length / 2.0;
+        let uv = |texel: u32| origin.saturating_add(texel.min(extent)) as f32 / texture_extent;
+        let inner_end = extent.saturating_sub(far).max(near.min(extent));
+        [
+            Span { start, end: start + near_size, uv_start: uv(0), uv_end: uv(near) },
+            Span { start: start + near_size, end: end - far_size, uv_start: uv(near), uv_end: uv(inner_end) },
+            Span { start: end - far_size, end, uv_start: uv(inner_end), uv_end: uv(extent) },
+        ]
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let columns = self.bands(
+            self.width,
+            self.insets.left,
+            self.insets.right,
+            self.region.x,
+            self.region.width,
+            self.texture.width(),
+        );
+        let rows = self.bands(
+            self.height,
+            self.insets.top,
+            self.insets.bottom,
+            self.region.y,
+            self.region.height,
+            self.texture.height(),
+        );
+        let horizontal_insets = self.insets.left.saturating_add(self.insets.right);
+        let vertical_insets = self.insets.top.saturating_add(self.insets.bottom);
+        let inner_width = self.region.width.saturating_sub(horizontal_insets) as f32 * self.border_scale;
+        let inner_height = self.region.height.saturating_sub(vertical_insets) as f32 * self.border_scale;
+
+        let y_down = renderer.is_y_down();
+        let mut vertices: Vec<Vertex2D> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        for (row_index, row) in rows.iter().enumerate() {
+            for (column_index, column) in columns.iter().enumerate() {
+                let is_center_column = column_index == 1;
+                let is_center_row = row_index == 1;
+                let mode = match (is_center_column, is_center_row) {
+                    (true, true) => self.center_mode,
+                    (false, false) => SliceMode::Stretch,
+                    _ => self.edge_mode,
+                };
+                let horizontal = if is_center_column { tile(*column, inner_width, mode) } else { vec![*column] };
+                let vertical = if is_center_row { tile(*row, inner_height, mode) } else { vec![*row] };
+
+                for y_span in &vertical {
+                    for x_span in &horizontal {
+                        if x_span.end - x_span.start <= 0.0 || y_span.end - y_span.start <= 0.0 {
+                            continue;
+                        }
+                        let base = vertices.len() as u32;
+                        vertices.extend_from_slice(&textured_quad_corners(
+                            [x_span.start, y_span.start, x_span.end, y_span.end],
+                            [x_span.uv_start, y_span.uv_start, x_span.uv_end, y_span.uv_end],
+                            y_down,
+                        ));
+                        indices.extend(QUAD_INDICES.iter().map(|index| base + index));
+                    }
+                }
+            }
+        }
+
+        renderer.submit_transformed(
+            Primitive::Triangles,
+            Material::Texture(self.texture.id()),
+            &vertices,
+            &indices,
+            &self.transform,
+        );
+    }
+}
+
+fn tile(span: Span, tile_size: f32, mode: SliceMode) -> Vec<Span> {
+    let length = span.end - span.start;
+    if mode == SliceMode::Stretch || tile_size <= f32::EPSILON || length <= 0.0 {
+        return vec![span];
+    }
+
+    let count = (length / tile_size).ceil() as usize;
+    (0..count)
+        .map(|index| {
+            let start = span.start + index as f32 * tile_size;
+            let end = (start + tile_size).min(span.end);
+            let fraction = (end - start) / tile_size;
+            Span {
+                start,
+                end,
+                uv_start: span.uv_start,
+                uv_end: span.uv_start + (span.uv_end - span.uv_start) * fraction,
+            }
+        })
+        .collect()
+}
+
+impl PhysicalObject for KNineSlice {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KNineSlice::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        Collider::from_rect(&self.transform, self.width / 2.0, self.height / 2.0)
+    }
+}
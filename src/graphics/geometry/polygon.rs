@@ -0,0 +1,136 @@
+use cgmath::Vector2;
+
+use crate::graphics::{renderer::{Material, Primitive, Renderer2D, Vertex2D}, transform::Transform2D};
+use super::{collision::{convex_hull, Collider, ConvexPolygon}, triangulate::{is_convex, triangulate}, PhysicalObject};
+
+pub struct KPolygon {
+    pub transform: Transform2D,
+    pub color: [f32; 4],
+    points: Vec<Vector2<f32>>,
+    holes: Vec<Vec<Vector2<f32>>>,
+    indices: Vec<u32>,
+}
+
+impl KPolygon {
+    pub fn new(points: &[(f32, f32)], color: [f32; 4]) -> Self {
+        let mut polygon = Self {
+            transform: Transform2D::default(),
+            color,
+            points: to_vectors(points),
+            holes: Vec::new(),
+            indices: Vec::new(),
+        };
+        polygon.retriangulate();
+        polygon
+    }
+
+    pub fn with_hole(mut self, hole: &[(f32, f32)]) -> Self {
+        self.add_hole(hole);
+        self
+    }
+
+    // Holes with fewer than three points enclose nothing and are ignored.
+    pub fn add_hole(&mut self, hole: &[(f32, f32)]) {
+        if hole.len() < 3 {
+            return;
+        }
+        self.holes.push(to_vectors(hole));
+        self.retriangulate();
+    }
+
+    pub fn set_points(&mut self, points: &[(f32, f32)]) {
+        self.points = to_vectors(points);
+        self.retriangulate();
+    }
+
+    pub fn points(&self) -> &[Vector2<f32>] {
+        &self.points
+    }
+
+    pub fn holes(&self) -> &[Vec<Vector2<f32>>] {
+        &self.holes
+    }
+
+    pub fn is_convex(&self) -> bool {
+        self.holes.is_empty() && is_convex(&self.points)
+    }
+
+    fn retriangulate(&mut self) {
+        self.indices = triangulate(&self.points, &self.holes);
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let vertices: Vec<Vertex2D> = self
+            .points
+            .iter()
+            .chain(self.holes.iter().flatten())
+            .map(|point| Vertex2D::colored(point.x, point.y, self.color))
+            .collect();
+        renderer.submit_transformed(Primitive::Triangles, Material::Color, &vertices, &self.indices, &self.transform);
+    }
+}
+
+fn to_vectors(points: &[(f32, f32)]) -> Vec<Vector2<f32>> {
+    points.iter().map(|&(x, y)| Vector2::new(x, y)).collect()
+}
+
+impl PhysicalObject for KPolygon {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KPolygon::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        let points: Vec<Vector2<f32>> = self.points.iter().map(|point| self.transform.apply(*point)).collect();
+        let polygon = if self.holes.is_empty() { ConvexPolygon::try_new(points.clone()) } else { None };
+        Collider::Polygon(polygon.unwrap_or_else(|| ConvexPolygon::new(convex_hull(&points))))
+    }
+
+    // Convex polygons collide as one piece, anything else as its triangles, so concave notches
+    // and holes stay empty.
+    fn colliders(&self) -> Vec<Collider> {
+        if self.is_convex() {
+            return vec![self.collider()];
+        }
+        let positions: Vec<Vector2<f32>> = self
+            .points
+            .iter()
+            .chain(self.holes.iter().flatten())
+            .map(|point| self.transform.apply(*point))
+            .collect();
+        self.indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let corners = triangle.iter().map(|&index| positions[index as usize]).collect();
+                Collider::Polygon(ConvexPolygon::new(corners))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::geometry::collision::Circle;
+
+    fn hits(polygon: &KPolygon, x: f32, y: f32, radius: f32) -> bool {
+        let circle = Collider::Circle(Circle::new(Vector2::new(x, y), radius));
+        polygon.colliders().iter().any(|collider| collider.collides_with(&circle).is_some())
+    }
+
+    #[test]
+    fn holes_do_not_collide() {
+        let frame = KPolygon::new(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)], [1.0; 4])
+            .with_hole(&[(2.0, 2.0), (8.0, 2.0), (8.0, 8.0), (2.0, 8.0)]);
+        assert!(!hits(&frame, 5.0, 5.0, 0.5));
+        assert!(hits(&frame, 1.0, 5.0, 0.5));
+        assert!(hits(&frame, 5.0, 9.0, 0.5));
+    }
+
+    #[test]
+    fn concave_notches_do_not_collide() {
+        let l_shape = KPolygon::new(&[(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (1.0, 1.0), (1.0, 4.0), (0.0, 4.0)], [1.0; 4]);
+        assert!(!hits(&l_shape, 3.0, 3.0, 0.5));
+        assert!(hits(&l_shape, 3.0, 0.5, 0.25));
+        assert!(KPolygon::new(&[], [1.0; 4]).with_hole(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0)]).colliders().is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Vector2};
+
+use crate::graphics::{renderer::{Material, Primitive, Renderer2D, Vertex2D}, transform::Transform2D};
+use super::{collision::{convex_hull, Collider, ConvexPolygon}, PhysicalObject};
+
+const EPSILON: f32 = 1e-6;
+const ROUND_STEP: f32 = PI / 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Square,
+    Round,
+}
+
+pub struct KPolyline {
+    pub transform: Transform2D,
+    pub points: Vec<Vector2<f32>>,
+    pub width: f32,
+    pub color: [f32; 4],
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: f32,
+    pub closed: bool,
+    pub dash: Option<Vec<f32>>,
+    pub dash_offset: f32,
+}
+
+impl KPolyline {
+    pub fn new(points: &[(f32, f32)], width: f32, color: [f32; 4]) -> Self {
+        Self {
+            transform: Transform2D::default(),
+            points: points.iter().map(|&(x, y)| Vector2::new(x, y)).collect(),
+            width,
+            color,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.0,
+            closed: false,
+            dash: None,
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    pub fn with_dash(mut self, pattern: &[f32], offset: f32) -> Self {
+        self.dash = Some(pattern.to_vec());
+        self.dash_offset = offset;
+        self
+    }
+
+    pub fn push_point(&mut self, x: f32, y: f32) {
+        self.points.push(Vector2::new(x, y));
+    }
+
+    pub fn length(&self) -> f32 {
+        let mut length: f32 = self.points.windows(2).map(|pair| (pair[1] - pair[0]).magnitude()).sum();
+        if self.closed && self.points.len() > 2 {
+            length += (self.points[0] - self.points[self.points.len() - 1]).magnitude();
+        }
+        length
+    }
+
+    pub fn tessellate(&self) -> (Vec<Vector2<f32>>, Vec<u32>) {
+        let mut mesh = StrokeMesh::default();
+        let half_width = self.width * 0.5;
+        if half_width <= 0.0 {
+            return (mesh.positions, mesh.indices);
+        }
+
+        match self.dash.as_deref() {
+            Some(pattern) if pattern.iter().sum::<f32>() > EPSILON => {
+                for run in dash_runs(&self.points, self.closed, pattern, self.dash_offset) {
+                    self.stroke_run(&mut mesh, &run, false, half_width);
+                }
+            }
+            _ => self.stroke_run(&mut mesh, &self.points, self.closed, half_width),
+        }
+        (mesh.positions, mesh.indices)
+    }
+
+    // The straight pieces of the stroke, following the dash pattern like `tessellate`.
+    fn segments(&self) -> Vec<(Vector2<f32>, Vector2<f32>)> {
+        let runs = match self.dash.as_deref() {
+            Some(pattern) if pattern.iter().sum::<f32>() > EPSILON => {
+                let runs = dash_runs(&self.points, self.closed, pattern, self.dash_offset);
+                runs.into_iter().map(|run| (run, false)).collect()
+            }
+            _ => vec![(self.points.clone(), self.closed)],
+        };
+        let mut segments = Vec::new();
+        for (run, closed) in runs {
+            segments.extend(run.windows(2).map(|pair| (pair[0], pair[1])));
+            if closed && run.len() > 2 {
+                segments.push((run[run.len() - 1], run[0]));
+            }
+        }
+        segments.retain(|(start, end)| (end - start).magnitude() > EPSILON);
+        segments
+    }
+
+    fn stroke_run(&self, mesh: &mut StrokeMesh, points: &[Vector2<f32>], closed: bool, half_width: f32) {
+        let mut path: Vec<Vector2<f32>> = Vec::with_capacity(points.len());
+        for &point in points {
+            if path.last().is_none_or(|last: &Vector2<f32>| (point - *last).magnitude2() > EPSILON * EPSILON) {
+                path.push(point);
+            }
+        }
+        let closed = closed && path.len() > 2;
+        if closed && (path[0] - path[path.len() - 1]).magnitude2() <= EPSILON * EPSILON {
+            path.pop();
+        }
+        if path.len() < 2 {
+            return;
+        }
+
+        let segment_count = if closed { path.len() } else { path.len() - 1 };
+        let directions: Vec<Vector2<f32>> = (0..segment_count)
+            .map(|i| (path[(i + 1) % path.len()] - path[i]).normalize())
+            .collect();
+
+        for (i, direction) in directions.iter().enumerate() {
+            let normal = perp(*direction) * half_width;
+            let (start, end) = (path[i], path[(i + 1) % path.len()]);
+            mesh.quad(start + normal, end + normal, end - normal, start - normal);
+        }
+
+        let joints = if closed { 0..path.len() } else { 1..path.len() - 1 };
+        for i in joints {
+            let incoming = directions[(i + segment_count - 1) % segment_count];
+            let outgoing = directions[i % segment_count];
+            self.join_segments(mesh, path[i], incoming, outgoing, half_width);
+        }
+
+        if !closed {
+            let last = path.len() - 1;
+            self.cap_end(mesh, path[0], -directions[0], half_width);
+            self.cap_end(mesh, path[last], directions[segment_count - 1], half_width);
+        }
+    }
+
+    fn join_segments(
+        &self,
+        mesh: &mut StrokeMesh,
+        point: Vector2<f32>,
+        incoming: Vector2<f32>,
+        outgoing: Vector2<f32>,
+        half_width: f32,
+    ) {
+        let turn = cross(incoming, outgoing);
+        if turn.abs() <= EPSILON && incoming.dot(outgoing) > 0.0 {
+            return;
+        }
+
+        let side = if turn > 0.0 { -1.0 } else { 1.0 };
+        let from = perp(incoming) * side;
+        let to = perp(outgoing) * side;
+
+        match self.join {
+            LineJoin::Bevel => mesh.triangle(point, point + from * half_width, point + to * half_width),
+            LineJoin::Round => mesh.fan(point, from, to, -side, half_width),
+            LineJoin::Miter => {
+                let bisector = from + to;
+                let cos_half = if bisector.magnitude2() > EPSILON { bisector.normalize().dot(from) } else { 0.0 };
+                if cos_half <= EPSILON || 1.0 / cos_half > self.miter_limit {
+                    mesh.triangle(point, point + from * half_width, point + to * half_width);
+                } else {
+                    let tip = point + bisector.normalize() * (half_width / cos_half);
+                    mesh.quad(point, point + from * half_width, tip, point + to * half_width);
+                }
+            }
+        }
+    }
+
+    fn cap_end(&self, mesh: &mut StrokeMesh, point: Vector2<f32>, direction: Vector2<f32>, half_width: f32) {
+        let normal = perp(direction) * half_width;
+        match self.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let extension = direction * half_width;
+                mesh.quad(point + normal, point + normal + extension, point - normal + extension, point - normal);
+            }
+            LineCap::Round => mesh.fan(point, -perp(direction), perp(direction), 1.0, half_width),
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let (positions, indices) = self.tessellate();
+        let vertices: Vec<Vertex2D> = positions
+            .iter()
+            .map(|position| Vertex2D::colored(position.x, position.y, self.color))
+            .collect();
+        renderer.submit_transformed(Primitive::Triangles, Material::Color, &vertices, &indices, &self.transform);
+    }
+}
+
+impl PhysicalObject for KPolyline {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KPolyline::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        let (positions, _) = self.tessellate();
+        let points: Vec<Vector2<f32>> = positions.iter().map(|point| self.transform.apply(*point)).collect();
+        Collider::Polygon(ConvexPolygon::new(convex_hull(&points)))
+    }
+
+    // One quad per stroked segment, so bent lines don't collide across their inside. Joins and
+    // caps are left out; an empty stroke has no colliders.
+    fn colliders(&self) -> Vec<Collider> {
+        let half_width = self.width * 0.5;
+        if half_width <= 0.0 {
+            return Vec::new();
+        }
+        self.segments()
+            .into_iter()
+            .map(|(start, end)| {
+                let normal = perp((end - start).normalize()) * half_width;
+                let corners = [start + normal, end + normal, end - normal, start - normal];
+                Collider::Polygon(ConvexPolygon::new(corners.iter().map(|&corner| self.transform.apply(corner)).collect()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct StrokeMesh {
+    positions: Vec<Vector2<f32>>,
+    indices: Vec<u32>,
+}
+
+impl StrokeMesh {
+    fn vertex(&mut self, position: Vector2<f32>) -> u32 {
+        self.positions.push(position);
+        self.positions.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) {
+        let (a, b, c) = (self.vertex(a), self.vertex(b), self.vertex(c));
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    fn quad(&mut self, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>, d: Vector2<f32>) {
+        let (a, b, c, d) = (self.vertex(a), self.vertex(b), self.vertex(c), self.vertex(d));
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+
+    // Sweeps from `from` to `to` (unit offsets around `center`) in the direction given by `side`.
+    fn fan(&mut self, center: Vector2<f32>, from: Vector2<f32>, to: Vector2<f32>, side: f32, radius: f32) {
+        let mut sweep = cross(from, to).atan2(from.dot(to));
+        if side * sweep < 0.0 {
+            sweep += side * 2.0 * PI;
+        }
+        let steps = ((sweep.abs() / ROUND_STEP).ceil() as u32).max(1);
+        let start_angle = from.y.atan2(from.x);
+        let hub = self.vertex(center);
+        let mut previous = self.vertex(center + from * radius);
+        for step in 1..=steps {
+            let angle = start_angle + sweep * step as f32 / steps as f32;
+            let current = self.vertex(center + Vector2::new(angle.cos(), angle.sin()) * radius);
+            self.indices.extend_from_slice(&[hub, previous, current]);
+            previous = current;
+        }
+    }
+}
+
+fn perp(direction: Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(-direction.y, direction.x)
+}
+
+fn cross(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dash_runs(points: &[Vector2<f32>], closed: bool, pattern: &[f32], offset: f32) -> Vec<Vec<Vector2<f32>>> {
+    let mut pattern: Vec<f32> = pattern.iter().map(|length| length.max(0.0)).collect();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+    let total: f32 = pattern.iter().sum();
+
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut skip = offset.rem_euclid(total);
+    while skip > 0.0 {
+        if skip >= remaining {
+            skip -= remaining;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        } else {
+            remaining -= skip;
+            skip = 0.0;
+        }
+    }
+
+    let mut path = points.to_vec();
+    if closed && path.len() > 2 {
+        path.push(path[0]);
+    }
+
+    let mut runs = Vec::new();
+    let mut current = Vec::new();
+    if let Some(&first) = path.first() {
+        if index % 2 == 0 {
+            current.push(first);
+        }
+    }
+
+    for pair in path.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let length = (end - start).magnitude();
+        if length <= EPSILON {
+            continue;
+        }
+        let direction = (end - start) / length;
+        let mut travelled = 0.0;
+        while length - travelled > remaining {
+            travelled += remaining;
+            let point = start + direction * travelled;
+            if index % 2 == 0 {
+                current.push(point);
+                runs.push(std::mem::take(&mut current));
+            } else {
+                current = vec![point];
+            }
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length - travelled;
+        if index % 2 == 0 {
+            current.push(end);
+        }
+    }
+
+    if index % 2 == 0 && current.len() > 1 {
+        runs.push(current);
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::geometry::collision::Circle;
+
+    fn circle(x: f32, y: f32, radius: f32) -> Collider {
+        Collider::Circle(Circle::new(Vector2::new(x, y), radius))
+    }
+
+    fn hits(polyline: &KPolyline, other: &Collider) -> bool {
+        polyline.colliders().iter().any(|collider| collider.collides_with(other).is_some())
+    }
+
+    #[test]
+    fn bent_lines_leave_their_inside_empty() {
+        let wall = KPolyline::new(&[(0.0, 10.0), (0.0, 0.0), (10.0, 0.0)], 1.0, [1.0; 4]);
+        assert_eq!(wall.colliders().len(), 2);
+        assert!(!hits(&wall, &circle(5.0, 5.0, 1.0)));
+        assert!(hits(&wall, &circle(5.0, 1.0, 1.0)));
+        assert!(hits(&wall, &circle(1.0, 5.0, 1.0)));
+    }
+
+    #[test]
+    fn closed_and_empty_strokes() {
+        let square = KPolyline::new(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)], 0.5, [1.0; 4]).closed(true);
+        assert_eq!(square.colliders().len(), 4);
+        assert!(!hits(&square, &circle(2.0, 2.0, 1.0)));
+        assert!(hits(&square, &circle(0.0, 2.0, 0.5)));
+
+        assert!(KPolyline::new(&[(0.0, 0.0), (4.0, 0.0)], 0.0, [1.0; 4]).colliders().is_empty());
+        assert!(KPolyline::new(&[(1.0, 1.0)], 2.0, [1.0; 4]).colliders().is_empty());
+        assert!(KPolyline::new(&[(1.0, 1.0), (1.0, 1.0)], 2.0, [1.0; 4]).colliders().is_empty());
+    }
+}
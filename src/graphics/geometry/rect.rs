@@ -0,0 +1,91 @@
+use crate::graphics::{renderer::{Material, Primitive, Renderer2D, Vertex2D}, transform::Transform2D};
+use super::{collision::Collider, PhysicalObject};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub color: [f32; 4],
+}
+
+pub struct KRect {
+    pub transform: Transform2D,
+    pub width: f32,
+    pub height: f32,
+    pub color: [f32; 4],
+    pub stroke: Option<Stroke>,
+}
+
+impl KRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, color: [f32; 4]) -> Self {
+        Self {
+            transform: Transform2D::new(x, y),
+            width,
+            height,
+            color,
+            stroke: None,
+        }
+    }
+
+    pub fn outlined(x: f32, y: f32, width: f32, height: f32, stroke_width: f32, stroke_color: [f32; 4]) -> Self {
+        Self::new(x, y, width, height, [0.0, 0.0, 0.0, 0.0]).with_stroke(stroke_width, stroke_color)
+    }
+
+    pub fn with_stroke(mut self, width: f32, color: [f32; 4]) -> Self {
+        self.stroke = Some(Stroke { width, color });
+        self
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let half_width = self.width / 2.0;
+        let half_height = self.height / 2.0;
+
+        let Some(stroke) = self.stroke else {
+            submit_rect(renderer, half_width, half_height, self.color, &self.transform);
+            return;
+        };
+
+        let inset = stroke.width.clamp(0.0, half_width.min(half_height));
+        let inner_width = half_width - inset;
+        let inner_height = half_height - inset;
+
+        if self.color[3] > 0.0 && inner_width > 0.0 && inner_height > 0.0 {
+            submit_rect(renderer, inner_width, inner_height, self.color, &self.transform);
+        }
+
+        let vertices = [
+            Vertex2D::colored(half_width, half_height, stroke.color),
+            Vertex2D::colored(half_width, -half_height, stroke.color),
+            Vertex2D::colored(-half_width, -half_height, stroke.color),
+            Vertex2D::colored(-half_width, half_height, stroke.color),
+            Vertex2D::colored(inner_width, inner_height, stroke.color),
+            Vertex2D::colored(inner_width, -inner_height, stroke.color),
+            Vertex2D::colored(-inner_width, -inner_height, stroke.color),
+            Vertex2D::colored(-inner_width, inner_height, stroke.color),
+        ];
+        let mut indices = Vec::with_capacity(24);
+        for side in 0..4 {
+            let next = (side + 1) % 4;
+            indices.extend_from_slice(&[side, next, next + 4, side, next + 4, side + 4]);
+        }
+        renderer.submit_transformed(Primitive::Triangles, Material::Color, &vertices, &indices, &self.transform);
+    }
+}
+
+fn submit_rect(renderer: &mut Renderer2D, half_width: f32, half_height: f32, color: [f32; 4], transform: &Transform2D) {
+    renderer.submit_quad_transformed(Material::Color, [
+        Vertex2D::colored(half_width, half_height, color),
+        Vertex2D::colored(half_width, -half_height, color),
+        Vertex2D::colored(-half_width, -half_height, color),
+        Vertex2D::colored(-half_width, half_height, color),
+    ], transform);
+}
+
+impl PhysicalObject for KRect {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        KRect::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        Collider::from_rect(&self.transform, self.width / 2.0, self.height / 2.0)
+    }
+}
@@ -0,0 +1,40 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::graphics::renderer::Renderer2D;
+use super::{collision::Collider, polygon::KPolygon, PhysicalObject};
+
+pub struct KTriangle(KPolygon);
+
+impl KTriangle {
+    pub fn new(a: (f32, f32), b: (f32, f32), c: (f32, f32), color: [f32; 4]) -> Self {
+        Self(KPolygon::new(&[a, b, c], color))
+    }
+
+    pub fn into_polygon(self) -> KPolygon {
+        self.0
+    }
+}
+
+impl Deref for KTriangle {
+    type Target = KPolygon;
+
+    fn deref(&self) -> &KPolygon {
+        &self.0
+    }
+}
+
+impl DerefMut for KTriangle {
+    fn deref_mut(&mut self) -> &mut KPolygon {
+        &mut self.0
+    }
+}
+
+impl PhysicalObject for KTriangle {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        self.0.draw(renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        self.0.collider()
+    }
+}
@@ -0,0 +1,244 @@
+use cgmath::Vector2;
+
+const EPSILON: f32 = 1e-7;
+
+fn cross(o: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+pub fn signed_area(points: &[Vector2<f32>]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    area * 0.5
+}
+
+pub fn is_convex(points: &[Vector2<f32>]) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+    let mut sign = 0.0f32;
+    for i in 0..points.len() {
+        let turn = cross(points[i], points[(i + 1) % points.len()], points[(i + 2) % points.len()]);
+        if turn.abs() <= EPSILON {
+            continue;
+        }
+        if sign == 0.0 {
+            sign = turn.signum();
+        } else if turn.signum() != sign {
+            return false;
+        }
+    }
+    sign != 0.0
+}
+
+fn point_in_triangle(point: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> bool {
+    cross(a, b, point) >= -EPSILON && cross(b, c, point) >= -EPSILON && cross(c, a, point) >= -EPSILON
+}
+
+fn segments_intersect(p1: Vector2<f32>, p2: Vector2<f32>, q1: Vector2<f32>, q2: Vector2<f32>) -> bool {
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+    ((d1 > EPSILON && d2 < -EPSILON) || (d1 < -EPSILON && d2 > EPSILON))
+        && ((d3 > EPSILON && d4 < -EPSILON) || (d3 < -EPSILON && d4 > EPSILON))
+}
+
+// Triangulates a simple polygon with optional holes. The returned indices refer to the
+// outer points followed by the points of each hole, in the order they were passed in.
+// Holes with fewer than three points are skipped and take up no indices.
+pub fn triangulate(outer: &[Vector2<f32>], holes: &[Vec<Vector2<f32>>]) -> Vec<u32> {
+    if outer.len() < 3 {
+        return Vec::new();
+    }
+    let mut positions: Vec<Vector2<f32>> = outer.to_vec();
+    let mut ring: Vec<usize> = (0..outer.len()).collect();
+    if signed_area(outer) < 0.0 {
+        ring.reverse();
+    }
+
+    let mut pending: Vec<Vec<usize>> = Vec::with_capacity(holes.len());
+    for hole in holes {
+        if hole.len() < 3 {
+            continue;
+        }
+        let start = positions.len();
+        positions.extend_from_slice(hole);
+        let mut hole_ring: Vec<usize> = (start..positions.len()).collect();
+        if signed_area(hole) > 0.0 {
+            hole_ring.reverse();
+        }
+        pending.push(hole_ring);
+    }
+
+    pending.sort_by(|a, b| {
+        let max_a = a.iter().map(|&i| positions[i].x).fold(f32::MIN, f32::max);
+        let max_b = b.iter().map(|&i| positions[i].x).fold(f32::MIN, f32::max);
+        max_b.total_cmp(&max_a)
+    });
+
+    for index in 0..pending.len() {
+        let hole = pending[index].clone();
+        bridge_hole(&positions, &mut ring, &hole, &pending[index + 1..]);
+    }
+
+    ear_clip(&positions, ring)
+}
+
+fn bridge_hole(positions: &[Vector2<f32>], ring: &mut Vec<usize>, hole: &[usize], remaining: &[Vec<usize>]) {
+    let hole_start = (0..hole.len())
+        .max_by(|&a, &b| positions[hole[a]].x.total_cmp(&positions[hole[b]].x))
+        .unwrap_or(0);
+    let hole_point = positions[hole[hole_start]];
+
+    let edges_of = |indices: &[usize]| -> Vec<(Vector2<f32>, Vector2<f32>)> {
+        (0..indices.len())
+            .map(|i| (positions[indices[i]], positions[indices[(i + 1) % indices.len()]]))
+            .collect()
+    };
+    let mut edges = edges_of(ring);
+    edges.extend(edges_of(hole));
+    for other in remaining {
+        edges.extend(edges_of(other));
+    }
+
+    let mut candidates: Vec<usize> = (0..ring.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        let da = (positions[ring[a]] - hole_point).x.powi(2) + (positions[ring[a]] - hole_point).y.powi(2);
+        let db = (positions[ring[b]] - hole_point).x.powi(2) + (positions[ring[b]] - hole_point).y.powi(2);
+        da.total_cmp(&db)
+    });
+
+    let bridge = candidates
+        .iter()
+        .copied()
+        .find(|&candidate| {
+            let target = positions[ring[candidate]];
+            edges.iter().all(|&(a, b)| !segments_intersect(hole_point, target, a, b))
+        })
+        .or_else(|| candidates.first().copied());
+    let Some(bridge) = bridge else {
+        return;
+    };
+
+    let mut merged = Vec::with_capacity(ring.len() + hole.len() + 2);
+    merged.extend_from_slice(&ring[..=bridge]);
+    for offset in 0..=hole.len() {
+        merged.push(hole[(hole_start + offset) % hole.len()]);
+    }
+    merged.extend_from_slice(&ring[bridge..]);
+    *ring = merged;
+}
+
+fn ear_clip(positions: &[Vector2<f32>], mut ring: Vec<usize>) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(ring.len().saturating_sub(2) * 3);
+
+    while ring.len() > 3 {
+        let count = ring.len();
+        let ear = (0..count).find(|&i| {
+            let prev = ring[(i + count - 1) % count];
+            let current = ring[i];
+            let next = ring[(i + 1) % count];
+            let (a, b, c) = (positions[prev], positions[current], positions[next]);
+            if cross(a, b, c) <= EPSILON {
+                return false;
+            }
+            ring.iter().all(|&other| {
+                let point = positions[other];
+                point == a || point == b || point == c || !point_in_triangle(point, a, b, c)
+            })
+        });
+
+        let ear = ear.unwrap_or_else(|| {
+            (0..count)
+                .max_by(|&i, &j| {
+                    let area = |k: usize| {
+                        cross(positions[ring[(k + count - 1) % count]], positions[ring[k]], positions[ring[(k + 1) % count]])
+                    };
+                    area(i).total_cmp(&area(j))
+                })
+                .unwrap_or(0)
+        });
+
+        indices.extend_from_slice(&[
+            ring[(ear + count - 1) % count] as u32,
+            ring[ear] as u32,
+            ring[(ear + 1) % count] as u32,
+        ]);
+        ring.remove(ear);
+    }
+
+    if ring.len() == 3 {
+        indices.extend(ring.iter().map(|&index| index as u32));
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f32, f32)]) -> Vec<Vector2<f32>> {
+        coords.iter().map(|&(x, y)| Vector2::new(x, y)).collect()
+    }
+
+    fn covered_area(positions: &[Vector2<f32>], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|corner| positions[triangle[corner] as usize]);
+                cross(a, b, c) * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn clips_concave_polygons() {
+        let outer = points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (1.0, 1.0), (1.0, 4.0), (0.0, 4.0)]);
+        let indices = triangulate(&outer, &[]);
+        assert_eq!(indices.len(), 4 * 3);
+        assert!((covered_area(&outer, &indices) - 7.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn clockwise_input_gives_counter_clockwise_triangles() {
+        let outer = points(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)]);
+        let indices = triangulate(&outer, &[]);
+        assert_eq!(indices.len(), 2 * 3);
+        assert!((covered_area(&outer, &indices) - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn holes_are_left_uncovered() {
+        let outer = points(&[(0.0, 0.0), (6.0, 0.0), (6.0, 6.0), (0.0, 6.0)]);
+        let holes = [
+            points(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]),
+            points(&[(3.0, 3.0), (5.0, 3.0), (5.0, 5.0), (3.0, 5.0)]),
+        ];
+        let indices = triangulate(&outer, &holes);
+        let positions: Vec<_> = outer.iter().chain(holes.iter().flatten()).copied().collect();
+        assert!(indices.iter().all(|&index| (index as usize) < positions.len()));
+        assert_eq!(indices.len(), (positions.len() + 2 * holes.len() - 2) * 3);
+        assert!((covered_area(&positions, &indices) - (36.0 - 1.0 - 4.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn holes_without_an_outline_give_nothing() {
+        let hole = points(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)]);
+        assert!(triangulate(&[], std::slice::from_ref(&hole)).is_empty());
+        assert!(triangulate(&points(&[(0.0, 0.0), (4.0, 0.0)]), &[hole]).is_empty());
+    }
+
+    #[test]
+    fn degenerate_holes_take_no_indices() {
+        let outer = points(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        let hole = points(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)]);
+        let indices = triangulate(&outer, &[points(&[(2.0, 2.0), (2.5, 2.0)]), hole.clone()]);
+        assert_eq!(indices, triangulate(&outer, &[hole]));
+        assert!(indices.iter().all(|&index| index < 8));
+    }
+}
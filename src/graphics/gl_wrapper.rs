@@ -1,19 +1,101 @@
-use core::panic;
-use std::{collections::HashMap, ffi::CString, mem, os::raw::c_void, ptr};
+use std::{cell::Cell, collections::HashMap, ffi::CString, mem, os::raw::c_void, ptr};
 
 use cgmath::{Matrix, Matrix4};
 use gl::types::{GLboolean, GLchar, GLenum, GLint, GLsizei, GLuint};
+use image::{DynamicImage, GenericImageView};
+
+use crate::error::{Error, Result, ShaderStage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlResource {
+    VertexArray,
+    Buffer,
+    Shader,
+    Program,
+    Texture,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlResourceCounts {
+    pub vertex_arrays: usize,
+    pub buffers: usize,
+    pub shaders: usize,
+    pub programs: usize,
+    pub textures: usize,
+}
+
+impl GlResourceCounts {
+    pub fn total(&self) -> usize {
+        self.vertex_arrays + self.buffers + self.shaders + self.programs + self.textures
+    }
+}
+
+#[cfg(debug_assertions)]
+mod tracker {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{GlResource, GlResourceCounts};
+
+    static LIVE: [AtomicUsize; 5] = [const { AtomicUsize::new(0) }; 5];
+
+    fn slot(resource: GlResource) -> &'static AtomicUsize {
+        &LIVE[resource as usize]
+    }
+
+    pub fn created(resource: GlResource) {
+        slot(resource).fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn deleted(resource: GlResource) {
+        slot(resource).fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn counts() -> GlResourceCounts {
+        GlResourceCounts {
+            vertex_arrays: slot(GlResource::VertexArray).load(Ordering::Relaxed),
+            buffers: slot(GlResource::Buffer).load(Ordering::Relaxed),
+            shaders: slot(GlResource::Shader).load(Ordering::Relaxed),
+            programs: slot(GlResource::Program).load(Ordering::Relaxed),
+            textures: slot(GlResource::Texture).load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+mod tracker {
+    use super::{GlResource, GlResourceCounts};
+
+    pub fn created(_resource: GlResource) {}
+
+    pub fn deleted(_resource: GlResource) {}
+
+    pub fn counts() -> GlResourceCounts {
+        GlResourceCounts::default()
+    }
+}
+
+// Live object counts are only tracked in debug builds; release builds always report zero.
+pub fn live_gl_resources() -> GlResourceCounts {
+    tracker::counts()
+}
 
 pub struct Vao {
     id: gl::types::GLuint,
 }
 
+impl Default for Vao {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Vao {
     pub fn new() -> Vao {
         let mut id = 0;
         unsafe {
             gl::GenVertexArrays(1, &mut id);
         }
+        tracker::created(GlResource::VertexArray);
         Vao {id}
     }
     pub fn bind(&self) {
@@ -28,6 +110,15 @@ impl Vao {
     }
 }
 
+impl Drop for Vao {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.id);
+        }
+        tracker::deleted(GlResource::VertexArray);
+    }
+}
+
 pub struct BufferObject {
     id: gl::types::GLuint,
     r#type: gl::types::GLenum,
@@ -40,6 +131,7 @@ impl BufferObject {
         unsafe {
             gl::GenBuffers(1, &mut id);
         }
+        tracker::created(GlResource::Buffer);
         BufferObject { id, r#type, usage }
     }
     pub fn bind(&self) {
@@ -56,7 +148,7 @@ impl BufferObject {
         unsafe {
             gl::BufferData(
                 self.r#type,
-                (data.len() * mem::size_of::<gl::types::GLfloat>()) as gl::types::GLsizeiptr,
+                mem::size_of_val(data) as gl::types::GLsizeiptr,
                 &data[0] as * const f32 as *const c_void,
                 self.usage
             )
@@ -66,14 +158,32 @@ impl BufferObject {
         unsafe {
             gl::BufferData(
                 self.r#type,
-                (data.len() * mem::size_of::<gl::types::GLfloat>()) as gl::types::GLsizeiptr,
+                mem::size_of_val(data) as gl::types::GLsizeiptr,
                 &data[0] as * const i32 as *const c_void,
                 self.usage
             )
         }
     }
+    pub fn store_data<T: Copy>(&self, data: &[T]) {
+        unsafe {
+            gl::BufferData(
+                self.r#type,
+                mem::size_of_val(data) as gl::types::GLsizeiptr,
+                data.as_ptr() as *const c_void,
+                self.usage
+            )
+        }
+    }
 }
 
+impl Drop for BufferObject {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+        tracker::deleted(GlResource::Buffer);
+    }
+}
 
 pub struct VertexAttribute {
     index: GLuint,
@@ -87,10 +197,10 @@ impl VertexAttribute {
         r#type: GLenum,
         normalized: GLboolean,
         stride: GLsizei,
-        pointer: *const c_void
+        offset: usize
     ) -> VertexAttribute {
         unsafe {
-            gl::VertexAttribPointer(index, size, r#type, normalized, stride, pointer);
+            gl::VertexAttribPointer(index, size, r#type, normalized, stride, offset as *const c_void);
         }
         VertexAttribute {index}
     }
@@ -107,57 +217,118 @@ impl VertexAttribute {
     }
 }
 
+pub struct Shader {
+    id: GLuint,
+}
+
+fn shader_stage(stage: GLenum) -> Result<ShaderStage> {
+    match stage {
+        gl::VERTEX_SHADER => Ok(ShaderStage::Vertex),
+        gl::FRAGMENT_SHADER => Ok(ShaderStage::Fragment),
+        gl::GEOMETRY_SHADER => Ok(ShaderStage::Geometry),
+        gl::TESS_CONTROL_SHADER => Ok(ShaderStage::TessControl),
+        gl::TESS_EVALUATION_SHADER => Ok(ShaderStage::TessEvaluation),
+        gl::COMPUTE_SHADER => Ok(ShaderStage::Compute),
+        _ => Err(Error::UnknownShaderStage { stage }),
+    }
+}
+
+impl Shader {
+    pub fn new(stage: GLenum, source: &str) -> Result<Shader> {
+        let shader_stage = shader_stage(stage)?;
+        let c_str = CString::new(source.as_bytes()).map_err(|_| Error::ShaderCompile {
+            stage: shader_stage,
+            log: "source contains a NUL byte".to_string(),
+        })?;
+
+        unsafe {
+            let id = gl::CreateShader(stage);
+            tracker::created(GlResource::Shader);
+            let shader = Shader { id };
+
+            gl::ShaderSource(shader.id, 1, &c_str.as_ptr(), ptr::null());
+            gl::CompileShader(shader.id);
+
+            let mut success: i32 = 0;
+            gl::GetShaderiv(shader.id, gl::COMPILE_STATUS, &mut success);
+            if success == 0 {
+                let mut log_len: i32 = 0;
+                gl::GetShaderiv(shader.id, gl::INFO_LOG_LENGTH, &mut log_len);
+                let mut log: Vec<u8> = Vec::with_capacity(log_len as usize);
+                gl::GetShaderInfoLog(
+                    shader.id,
+                    log_len,
+                    &mut log_len,
+                    log.as_mut_ptr() as *mut GLchar,
+                );
+                log.set_len(log_len as usize);
+                return Err(Error::ShaderCompile {
+                    stage: shader_stage,
+                    log: String::from_utf8_lossy(&log).into_owned(),
+                });
+            }
+
+            Ok(shader)
+        }
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteShader(self.id);
+        }
+        tracker::deleted(GlResource::Shader);
+    }
+}
+
 pub struct ShaderProgram {
     program_handle: u32,
     uniform_ids: HashMap<String, GLint>
 }
 
 impl ShaderProgram {
-    pub fn new(vertex_shader_source: &str, fragment_shader_source: &str) -> ShaderProgram {
-        unsafe {
-            let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-            let c_str_vert = CString::new(vertex_shader_source.as_bytes()).unwrap();
-            gl::ShaderSource(vertex_shader, 1, &c_str_vert.as_ptr(), ptr::null());
-            gl::CompileShader(vertex_shader);
-            Self::check_shader_compile(vertex_shader);
-
-            let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-            let c_str_frag = CString::new(fragment_shader_source.as_bytes()).unwrap();
-            gl::ShaderSource(fragment_shader, 1, &c_str_frag.as_ptr(), ptr::null());
-            gl::CompileShader(fragment_shader);
-            Self::check_shader_compile(fragment_shader);
+    pub fn new(vertex_shader_source: &str, fragment_shader_source: &str) -> Result<ShaderProgram> {
+        let vertex_shader = Shader::new(gl::VERTEX_SHADER, vertex_shader_source)?;
+        let fragment_shader = Shader::new(gl::FRAGMENT_SHADER, fragment_shader_source)?;
 
+        unsafe {
             let program_handle = gl::CreateProgram();
-            gl::AttachShader(program_handle, vertex_shader);
-            gl::AttachShader(program_handle, fragment_shader);
-            gl::LinkProgram(program_handle);
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
-
-            ShaderProgram {
+            tracker::created(GlResource::Program);
+            let program = ShaderProgram {
                 program_handle,
                 uniform_ids: HashMap::new(),
-            }
-        }
-    }
+            };
+
+            gl::AttachShader(program_handle, vertex_shader.id());
+            gl::AttachShader(program_handle, fragment_shader.id());
+            gl::LinkProgram(program_handle);
+            gl::DetachShader(program_handle, vertex_shader.id());
+            gl::DetachShader(program_handle, fragment_shader.id());
 
-    fn check_shader_compile(shader: u32) {
-        unsafe {
             let mut success: i32 = 0;
-            gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+            gl::GetProgramiv(program_handle, gl::LINK_STATUS, &mut success);
             if success == 0 {
                 let mut log_len: i32 = 0;
-                gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut log_len);
+                gl::GetProgramiv(program_handle, gl::INFO_LOG_LENGTH, &mut log_len);
                 let mut log: Vec<u8> = Vec::with_capacity(log_len as usize);
-                gl::GetShaderInfoLog(
-                    shader,
+                gl::GetProgramInfoLog(
+                    program_handle,
                     log_len,
                     &mut log_len,
                     log.as_mut_ptr() as *mut GLchar,
                 );
                 log.set_len(log_len as usize);
-                panic!("Shader compilation failed: {}", String::from_utf8_lossy(&log));
+                return Err(Error::ShaderLink {
+                    log: String::from_utf8_lossy(&log).into_owned(),
+                });
             }
+
+            Ok(program)
         }
     }
 
@@ -173,29 +344,303 @@ impl ShaderProgram {
         }
     }
 
-    pub fn create_uniform(&mut self, uniform_name: &str) {
+    pub fn create_uniform(&mut self, uniform_name: &str) -> Result<()> {
+        let c_name = CString::new(uniform_name).map_err(|_| Error::UniformNotFound {
+            name: uniform_name.to_string(),
+        })?;
         let uniform_location = unsafe {
-            gl::GetUniformLocation(
-                self.program_handle,
-                CString::new(uniform_name).unwrap().as_c_str().as_ptr(),
-            )
+            gl::GetUniformLocation(self.program_handle, c_name.as_ptr())
         };
         if uniform_location < 0 {
-            panic!("Cannot locate uniform: {}", uniform_name);
-        } else {
-            self.uniform_ids
-                .insert(uniform_name.to_string(), uniform_location);
+            return Err(Error::UniformNotFound {
+                name: uniform_name.to_string(),
+            });
         }
+        self.uniform_ids
+            .insert(uniform_name.to_string(), uniform_location);
+        Ok(())
     }
 
-    pub fn set_matrix4fv_uniform(&self, uniform_name: &str, matrix: &Matrix4<f32>) {
+    pub fn set_matrix4fv_uniform(&self, uniform_name: &str, matrix: &Matrix4<f32>) -> Result<()> {
+        let location = *self.uniform_ids.get(uniform_name).ok_or_else(|| Error::UniformNotFound {
+            name: uniform_name.to_string(),
+        })?;
         unsafe {
-            gl::UniformMatrix4fv(
-                self.uniform_ids[uniform_name],
-                1,
-                gl::FALSE,
-                matrix.as_ptr(),
+            gl::UniformMatrix4fv(location, 1, gl::FALSE, matrix.as_ptr());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.program_handle);
+        }
+        tracker::deleted(GlResource::Program);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureWrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+impl TextureWrap {
+    fn gl_value(self) -> GLint {
+        (match self {
+            TextureWrap::Repeat => gl::REPEAT,
+            TextureWrap::MirroredRepeat => gl::MIRRORED_REPEAT,
+            TextureWrap::ClampToEdge => gl::CLAMP_TO_EDGE,
+        }) as GLint
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MipmapPolicy {
+    Disabled,
+    OnUpload,
+    OnEveryUpdate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureOptions {
+    pub min_filter: TextureFilter,
+    pub mag_filter: TextureFilter,
+    pub wrap_s: TextureWrap,
+    pub wrap_t: TextureWrap,
+    pub mipmaps: MipmapPolicy,
+}
+
+impl TextureOptions {
+    pub fn nearest() -> Self {
+        TextureOptions {
+            min_filter: TextureFilter::Nearest,
+            mag_filter: TextureFilter::Nearest,
+            ..Default::default()
+        }
+    }
+
+    fn gl_min_filter(&self) -> GLint {
+        (match (self.min_filter, self.mipmaps) {
+            (TextureFilter::Nearest, MipmapPolicy::Disabled) => gl::NEAREST,
+            (TextureFilter::Linear, MipmapPolicy::Disabled) => gl::LINEAR,
+            (TextureFilter::Nearest, _) => gl::NEAREST_MIPMAP_NEAREST,
+            (TextureFilter::Linear, _) => gl::LINEAR_MIPMAP_LINEAR,
+        }) as GLint
+    }
+
+    fn gl_mag_filter(&self) -> GLint {
+        (match self.mag_filter {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR,
+        }) as GLint
+    }
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        TextureOptions {
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrap::ClampToEdge,
+            wrap_t: TextureWrap::ClampToEdge,
+            mipmaps: MipmapPolicy::Disabled,
+        }
+    }
+}
+
+pub struct Texture2D {
+    id: GLuint,
+    width: u32,
+    height: u32,
+    options: TextureOptions,
+    // Set while the mip levels have never been generated from uploaded pixels.
+    mipmaps_pending: Cell<bool>,
+}
+
+impl Texture2D {
+    pub fn from_image(image: &DynamicImage, options: TextureOptions) -> Texture2D {
+        let (width, height) = image.dimensions();
+        let data = image.to_rgba8().into_raw();
+        Self::create(width, height, data.as_ptr() as *const c_void, options)
+    }
+
+    pub fn from_rgba(width: u32, height: u32, data: &[u8], options: TextureOptions) -> Result<Texture2D> {
+        check_rgba_len(width, height, data)?;
+        Ok(Self::create(width, height, data.as_ptr() as *const c_void, options))
+    }
+
+    pub fn empty(width: u32, height: u32, options: TextureOptions) -> Texture2D {
+        Self::create(width, height, ptr::null(), options)
+    }
+
+    fn create(width: u32, height: u32, data: *const c_void, options: TextureOptions) -> Texture2D {
+        let mut id = 0;
+        unsafe {
+            gl::GenTextures(1, &mut id);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+            tracker::created(GlResource::Texture);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA as GLint,
+                width as GLsizei,
+                height as GLsizei,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data,
             );
         }
+
+        // Without pixels there is nothing to build mip levels from yet, so the first update does it.
+        let texture = Texture2D { id, width, height, options, mipmaps_pending: Cell::new(data.is_null()) };
+        texture.apply_options();
+        if options.mipmaps != MipmapPolicy::Disabled && !data.is_null() {
+            texture.generate_mipmaps();
+        }
+        texture
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
     }
-}
\ No newline at end of file
+
+    pub fn options(&self) -> TextureOptions {
+        self.options
+    }
+
+    pub fn set_options(&mut self, options: TextureOptions) {
+        let needs_mipmaps = self.options.mipmaps == MipmapPolicy::Disabled && options.mipmaps != MipmapPolicy::Disabled;
+        self.options = options;
+        self.apply_options();
+        if needs_mipmaps {
+            self.generate_mipmaps();
+        }
+    }
+
+    pub fn bind(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+        }
+    }
+
+    pub fn unbind(&self) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn update_sub_image(&self, x: u32, y: u32, width: u32, height: u32, data: &[u8]) -> Result<()> {
+        let right = x.checked_add(width).filter(|&right| right <= self.width);
+        let bottom = y.checked_add(height).filter(|&bottom| bottom <= self.height);
+        if right.is_none() || bottom.is_none() {
+            return Err(Error::TextureBounds { x, y, width, height });
+        }
+        check_rgba_len(width, height, data)?;
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                x as GLint,
+                y as GLint,
+                width as GLsizei,
+                height as GLsizei,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data.as_ptr() as *const c_void,
+            );
+        }
+        let first_upload = self.mipmaps_pending.replace(false);
+        match self.options.mipmaps {
+            MipmapPolicy::OnEveryUpdate => self.generate_mipmaps(),
+            MipmapPolicy::OnUpload if first_upload => self.generate_mipmaps(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn generate_mipmaps(&self) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::GenerateMipmap(gl::TEXTURE_2D);
+        }
+    }
+
+    fn apply_options(&self) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, self.options.gl_min_filter());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, self.options.gl_mag_filter());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, self.options.wrap_s.gl_value());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, self.options.wrap_t.gl_value());
+        }
+    }
+}
+
+// Largest width or height the current context accepts for a 2D texture.
+pub fn max_texture_size() -> u32 {
+    let mut size: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut size);
+    }
+    size.max(0) as u32
+}
+
+fn check_rgba_len(width: u32, height: u32, data: &[u8]) -> Result<()> {
+    let expected = (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(4));
+    match expected {
+        Some(expected) if expected == data.len() => Ok(()),
+        expected => Err(Error::TextureData { expected: expected.unwrap_or(usize::MAX), actual: data.len() }),
+    }
+}
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteTextures(1, &self.id);
+        }
+        tracker::deleted(GlResource::Texture);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgba_length_checks_do_not_overflow() {
+        assert!(check_rgba_len(2, 3, &[0; 24]).is_ok());
+        assert!(matches!(check_rgba_len(2, 3, &[0; 23]), Err(Error::TextureData { expected: 24, actual: 23 })));
+        assert!(check_rgba_len(u32::MAX, u32::MAX, &[]).is_err());
+        assert!(check_rgba_len(65536, 65536, &[]).is_err());
+    }
+
+    #[test]
+    fn unknown_shader_stages_are_rejected() {
+        assert_eq!(shader_stage(gl::FRAGMENT_SHADER).unwrap(), ShaderStage::Fragment);
+        assert_eq!(shader_stage(gl::TESS_EVALUATION_SHADER).unwrap(), ShaderStage::TessEvaluation);
+        assert!(matches!(shader_stage(gl::TEXTURE_2D), Err(Error::UnknownShaderStage { stage: gl::TEXTURE_2D })));
+    }
+}
@@ -1,3 +1,12 @@
 pub mod window;
 pub mod gl_wrapper;
-pub mod geometry;
\ No newline at end of file
+pub mod geometry;
+pub mod renderer;
+pub mod text;
+pub mod camera;
+pub mod transform;
+pub mod sprite;
+pub mod animation;
+pub mod atlas;
+pub mod tilemap;
+pub mod tiled;
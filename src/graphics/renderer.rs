@@ -0,0 +1,477 @@
+use std::mem;
+
+use cgmath::{Matrix4, SquareMatrix};
+use gl::types::{GLfloat, GLsizei};
+
+use crate::error::Result;
+use crate::graphics::camera::Camera2D;
+use crate::graphics::gl_wrapper::{BufferObject, ShaderProgram, Vao, VertexAttribute};
+use crate::graphics::transform::Transform2D;
+
+const SDF_VERTEX_SHADER: &str = r#"
+    #version 330 core
+    layout(location = 0) in vec3 aPos;
+    layout(location = 1) in vec4 aColor;
+    layout(location = 2) in vec2 aTexCoord;
+    layout(location = 3) in vec3 aModelRow0;
+    layout(location = 4) in vec3 aModelRow1;
+    layout(location = 5) in vec4 aParams;
+    uniform mat4 uViewProjection;
+    out vec4 vColor;
+    out vec2 vLocal;
+    out vec4 vParams;
+    void main() {
+        vec3 local = vec3(aPos.xy, 1.0);
+        vec2 world = vec2(dot(aModelRow0, local), dot(aModelRow1, local));
+        gl_Position = uViewProjection * vec4(world, aPos.z, 1.0);
+        vColor = aColor;
+        vLocal = aTexCoord;
+        vParams = aParams;
+    }
+"#;
+
+const SDF_FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    const float TAU = 6.28318530718;
+    in vec4 vColor;
+    in vec2 vLocal;
+    in vec4 vParams;
+    out vec4 FragColor;
+    void main() {
+        float radius = length(vLocal);
+        float distance = max(radius - 1.0, vParams.x - radius);
+        if (vParams.w > 0.5) {
+            float relative = mod(atan(vLocal.y, vLocal.x) - vParams.y, TAU);
+            float outside = relative <= vParams.z
+                ? -min(relative, vParams.z - relative)
+                : min(relative - vParams.z, TAU - relative);
+            distance = max(distance, outside * radius);
+        }
+        float coverage = clamp(0.5 - distance / max(fwidth(distance), 1e-5), 0.0, 1.0);
+        FragColor = vec4(vColor.rgb, vColor.a * coverage);
+    }
+"#;
+
+const VIEW_PROJECTION_UNIFORM: &str = "uViewProjection";
+pub(crate) const QUAD_INDICES: [u32; 6] = [0, 1, 3, 1, 2, 3];
+
+const COLOR_VERTEX_SHADER: &str = r#"
+    #version 330 core
+    layout(location = 0) in vec3 aPos;
+    layout(location = 1) in vec4 aColor;
+    layout(location = 2) in vec2 aTexCoord;
+    layout(location = 3) in vec3 aModelRow0;
+    layout(location = 4) in vec3 aModelRow1;
+    uniform mat4 uViewProjection;
+    out vec4 vColor;
+    void main() {
+        vec3 local = vec3(aPos.xy, 1.0);
+        vec2 world = vec2(dot(aModelRow0, local), dot(aModelRow1, local));
+        gl_Position = uViewProjection * vec4(world, aPos.z, 1.0);
+        vColor = aColor;
+    }
+"#;
+
+const COLOR_FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    in vec4 vColor;
+    out vec4 FragColor;
+    void main() {
+        FragColor = vColor;
+    }
+"#;
+
+const TEXTURE_VERTEX_SHADER: &str = r#"
+    #version 330 core
+    layout(location = 0) in vec3 aPos;
+    layout(location = 1) in vec4 aColor;
+    layout(location = 2) in vec2 aTexCoord;
+    layout(location = 3) in vec3 aModelRow0;
+    layout(location = 4) in vec3 aModelRow1;
+    uniform mat4 uViewProjection;
+    out vec4 vColor;
+    out vec2 TexCoord;
+    void main() {
+        vec3 local = vec3(aPos.xy, 1.0);
+        vec2 world = vec2(dot(aModelRow0, local), dot(aModelRow1, local));
+        gl_Position = uViewProjection * vec4(world, aPos.z, 1.0);
+        vColor = aColor;
+        TexCoord = aTexCoord;
+    }
+"#;
+
+const TEXTURE_FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    in vec4 vColor;
+    in vec2 TexCoord;
+    out vec4 FragColor;
+    uniform sampler2D texture1;
+    void main() {
+        FragColor = texture(texture1, TexCoord) * vColor;
+    }
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Vertex2D {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+    pub tex_coords: [f32; 2],
+    pub model: [[f32; 3]; 2],
+    pub params: [f32; 4],
+}
+
+const IDENTITY_MODEL: [[f32; 3]; 2] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+impl Vertex2D {
+    pub fn colored(x: f32, y: f32, color: [f32; 4]) -> Self {
+        Vertex2D { position: [x, y, 0.0], color, tex_coords: [0.0, 0.0], model: IDENTITY_MODEL, params: [0.0; 4] }
+    }
+
+    pub fn textured(x: f32, y: f32, u: f32, v: f32) -> Self {
+        Vertex2D { position: [x, y, 0.0], color: [1.0, 1.0, 1.0, 1.0], tex_coords: [u, v], model: IDENTITY_MODEL, params: [0.0; 4] }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Triangles,
+    Lines,
+}
+
+impl Primitive {
+    fn gl_mode(self) -> gl::types::GLenum {
+        match self {
+            Primitive::Triangles => gl::TRIANGLES,
+            Primitive::Lines => gl::LINES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    Color,
+    Texture(u32),
+    Sdf,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Batch {
+    primitive: Primitive,
+    material: Material,
+    index_start: usize,
+    index_count: usize,
+}
+
+fn configure_vertex_layout() {
+    let stride = mem::size_of::<Vertex2D>() as GLsizei;
+
+    let position_attribute = VertexAttribute::new(
+        0,
+        3,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        0,
+    );
+    position_attribute.enable();
+
+    let color_attribute = VertexAttribute::new(
+        1,
+        4,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        3 * mem::size_of::<GLfloat>(),
+    );
+    color_attribute.enable();
+
+    let texcoord_attribute = VertexAttribute::new(
+        2,
+        2,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        7 * mem::size_of::<GLfloat>(),
+    );
+    texcoord_attribute.enable();
+
+    let model_row0_attribute = VertexAttribute::new(
+        3,
+        3,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        9 * mem::size_of::<GLfloat>(),
+    );
+    model_row0_attribute.enable();
+
+    let model_row1_attribute = VertexAttribute::new(
+        4,
+        3,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        12 * mem::size_of::<GLfloat>(),
+    );
+    model_row1_attribute.enable();
+
+    let params_attribute = VertexAttribute::new(
+        5,
+        4,
+        gl::FLOAT,
+        gl::FALSE,
+        stride,
+        15 * mem::size_of::<GLfloat>(),
+    );
+    params_attribute.enable();
+}
+
+pub struct StaticMesh {
+    vao: Vao,
+    vbo: BufferObject,
+    ibo: BufferObject,
+    primitive: Primitive,
+    material: Material,
+    index_count: usize,
+}
+
+impl StaticMesh {
+    pub fn new(primitive: Primitive, material: Material) -> Self {
+        let vao = Vao::new();
+        vao.bind();
+        let vbo = BufferObject::new(gl::ARRAY_BUFFER, gl::STATIC_DRAW);
+        vbo.bind();
+        let ibo = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, gl::STATIC_DRAW);
+        ibo.bind();
+        configure_vertex_layout();
+        vao.unbind();
+        vbo.unbind();
+        ibo.unbind();
+
+        Self { vao, vbo, ibo, primitive, material, index_count: 0 }
+    }
+
+    pub fn upload(&mut self, vertices: &[Vertex2D], indices: &[u32]) {
+        self.vao.bind();
+        self.vbo.bind();
+        self.vbo.store_data(vertices);
+        self.ibo.bind();
+        self.ibo.store_data(indices);
+        self.vao.unbind();
+        self.index_count = indices.len();
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index_count == 0
+    }
+}
+
+pub struct Renderer2D {
+    vao: Vao,
+    vbo: BufferObject,
+    ibo: BufferObject,
+    color_shader: ShaderProgram,
+    texture_shader: ShaderProgram,
+    sdf_shader: ShaderProgram,
+    vertices: Vec<Vertex2D>,
+    indices: Vec<u32>,
+    batches: Vec<Batch>,
+    view_projection: Matrix4<f32>,
+    y_down: bool,
+    draw_calls: usize,
+}
+
+impl Renderer2D {
+    pub fn new() -> Result<Self> {
+        let vao = Vao::new();
+        vao.bind();
+
+        let vbo = BufferObject::new(gl::ARRAY_BUFFER, gl::DYNAMIC_DRAW);
+        vbo.bind();
+
+        let ibo = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, gl::DYNAMIC_DRAW);
+        ibo.bind();
+
+        configure_vertex_layout();
+
+        vao.unbind();
+        vbo.unbind();
+        ibo.unbind();
+
+        let mut color_shader = ShaderProgram::new(COLOR_VERTEX_SHADER, COLOR_FRAGMENT_SHADER)?;
+        color_shader.create_uniform(VIEW_PROJECTION_UNIFORM)?;
+        let mut texture_shader = ShaderProgram::new(TEXTURE_VERTEX_SHADER, TEXTURE_FRAGMENT_SHADER)?;
+        texture_shader.create_uniform(VIEW_PROJECTION_UNIFORM)?;
+        let mut sdf_shader = ShaderProgram::new(SDF_VERTEX_SHADER, SDF_FRAGMENT_SHADER)?;
+        sdf_shader.create_uniform(VIEW_PROJECTION_UNIFORM)?;
+
+        Ok(Self {
+            vao,
+            vbo,
+            ibo,
+            color_shader,
+            texture_shader,
+            sdf_shader,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            batches: Vec::new(),
+            view_projection: Matrix4::identity(),
+            y_down: false,
+            draw_calls: 0,
+        })
+    }
+
+    pub fn set_view_projection(&mut self, view_projection: Matrix4<f32>) {
+        if view_projection != self.view_projection {
+            self.flush();
+            self.view_projection = view_projection;
+        }
+    }
+
+    pub fn set_camera(&mut self, camera: &Camera2D) {
+        self.set_view_projection(camera.view_projection());
+        self.y_down = camera.coordinate_system().is_y_down();
+    }
+
+    pub fn is_y_down(&self) -> bool {
+        self.y_down
+    }
+
+    pub fn submit(&mut self, primitive: Primitive, material: Material, vertices: &[Vertex2D], indices: &[u32]) {
+        if vertices.is_empty() || indices.is_empty() {
+            return;
+        }
+
+        let base = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(vertices);
+        self.indices.extend(indices.iter().map(|index| base + index));
+
+        match self.batches.last_mut() {
+            Some(batch) if batch.primitive == primitive && batch.material == material => {
+                batch.index_count += indices.len();
+            }
+            _ => self.batches.push(Batch {
+                primitive,
+                material,
+                index_start: self.indices.len() - indices.len(),
+                index_count: indices.len(),
+            }),
+        }
+    }
+
+    pub fn submit_transformed(
+        &mut self,
+        primitive: Primitive,
+        material: Material,
+        vertices: &[Vertex2D],
+        indices: &[u32],
+        transform: &Transform2D,
+    ) {
+        let model = transform.affine_rows();
+        let start = self.vertices.len();
+        self.submit(primitive, material, vertices, indices);
+        for vertex in &mut self.vertices[start..] {
+            vertex.model = model;
+        }
+    }
+
+    pub fn submit_quad(&mut self, material: Material, corners: [Vertex2D; 4]) {
+        self.submit(Primitive::Triangles, material, &corners, &QUAD_INDICES);
+    }
+
+    pub fn submit_quad_transformed(&mut self, material: Material, corners: [Vertex2D; 4], transform: &Transform2D) {
+        self.submit_transformed(Primitive::Triangles, material, &corners, &QUAD_INDICES, transform);
+    }
+
+    pub fn submit_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: [f32; 4]) {
+        let vertices = [Vertex2D::colored(x1, y1, color), Vertex2D::colored(x2, y2, color)];
+        self.submit(Primitive::Lines, Material::Color, &vertices, &[0, 1]);
+    }
+
+    // Starts counting draw calls for a new frame.
+    pub fn begin_frame(&mut self) {
+        self.draw_calls = 0;
+    }
+
+    // Draws whatever is still batched; `draw_calls` then covers the whole frame.
+    pub fn end_frame(&mut self) {
+        self.flush();
+    }
+
+    pub fn flush(&mut self) {
+        if self.batches.is_empty() {
+            return;
+        }
+
+        self.vao.bind();
+        self.vbo.bind();
+        self.vbo.store_data(&self.vertices);
+        self.ibo.bind();
+        self.ibo.store_data(&self.indices);
+
+        for batch in &self.batches {
+            self.bind_material(batch.material);
+
+            unsafe {
+                gl::DrawElements(
+                    batch.primitive.gl_mode(),
+                    batch.index_count as GLsizei,
+                    gl::UNSIGNED_INT,
+                    (batch.index_start * mem::size_of::<u32>()) as *const _,
+                );
+            }
+            self.draw_calls += 1;
+        }
+
+        self.vao.unbind();
+        ShaderProgram::unbind();
+
+        self.vertices.clear();
+        self.indices.clear();
+        self.batches.clear();
+    }
+
+    pub fn draw_mesh(&mut self, mesh: &StaticMesh) {
+        self.flush();
+        if mesh.is_empty() {
+            return;
+        }
+
+        mesh.vao.bind();
+        self.bind_material(mesh.material);
+        unsafe {
+            gl::DrawElements(mesh.primitive.gl_mode(), mesh.index_count as GLsizei, gl::UNSIGNED_INT, std::ptr::null());
+        }
+        self.draw_calls += 1;
+        mesh.vao.unbind();
+        ShaderProgram::unbind();
+    }
+
+    fn bind_material(&self, material: Material) {
+        let shader = match material {
+            Material::Color => &self.color_shader,
+            Material::Sdf => &self.sdf_shader,
+            Material::Texture(texture_id) => {
+                unsafe {
+                    gl::ActiveTexture(gl::TEXTURE0);
+                    gl::BindTexture(gl::TEXTURE_2D, texture_id);
+                }
+                &self.texture_shader
+            }
+        };
+        shader.bind();
+        shader
+            .set_matrix4fv_uniform(VIEW_PROJECTION_UNIFORM, &self.view_projection)
+            .expect("view-projection uniform is registered in Renderer2D::new");
+    }
+
+    // Draw calls issued since the last `begin_frame`, across every flush and mesh.
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls
+    }
+}
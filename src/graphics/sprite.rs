@@ -0,0 +1,179 @@
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+    geometry::{collision::Collider, image::{submit_textured_quad, KImage}, PhysicalObject},
+    gl_wrapper::Texture2D,
+    renderer::Renderer2D,
+    transform::Transform2D,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextureRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn full(texture: &Texture2D) -> Self {
+        Self::new(0, 0, texture.width(), texture.height())
+    }
+
+    pub fn uv(&self, texture: &Texture2D) -> [f32; 4] {
+        let texture_width = texture.width().max(1) as f32;
+        let texture_height = texture.height().max(1) as f32;
+        [
+            self.x as f32 / texture_width,
+            self.y as f32 / texture_height,
+            (self.x + self.width) as f32 / texture_width,
+            (self.y + self.height) as f32 / texture_height,
+        ]
+    }
+}
+
+pub struct Sprite {
+    pub transform: Transform2D,
+    pub width: f32,
+    pub height: f32,
+    pub texture: Rc<Texture2D>,
+    pub region: TextureRegion,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Sprite {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, texture: Rc<Texture2D>, region: TextureRegion) -> Self {
+        Self {
+            transform: Transform2D::new(x, y),
+            width,
+            height,
+            texture,
+            region,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    pub fn set_frame(&mut self, sheet: &SpriteSheet, index: usize) {
+        if let Some(region) = sheet.frame(index) {
+            self.region = region;
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let [mut left, mut top, mut right, mut bottom] = self.region.uv(&self.texture);
+        if self.flip_x {
+            std::mem::swap(&mut left, &mut right);
+        }
+        if self.flip_y {
+            std::mem::swap(&mut top, &mut bottom);
+        }
+        submit_textured_quad(renderer, &self.texture, self.width, self.height, [left, top, right, bottom], &self.transform);
+    }
+}
+
+impl From<KImage> for Sprite {
+    fn from(image: KImage) -> Self {
+        let region = TextureRegion::full(&image.texture);
+        let mut sprite = Sprite::new(0.0, 0.0, image.width, image.height, image.texture, region);
+        sprite.transform = image.transform;
+        sprite
+    }
+}
+
+impl PhysicalObject for Sprite {
+    fn draw(&self, renderer: &mut Renderer2D) {
+        Sprite::draw(self, renderer);
+    }
+
+    fn collider(&self) -> Collider {
+        Collider::from_rect(&self.transform, self.width / 2.0, self.height / 2.0)
+    }
+}
+
+pub struct SpriteSheet {
+    texture: Rc<Texture2D>,
+    frames: Vec<TextureRegion>,
+    names: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+    pub fn new(texture: Rc<Texture2D>) -> Self {
+        Self {
+            texture,
+            frames: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    // Slices the texture row by row; `margin` surrounds the whole sheet and `spacing` separates frames.
+    pub fn from_grid(texture: Rc<Texture2D>, frame_width: u32, frame_height: u32, spacing: u32, margin: u32) -> Self {
+        let mut sheet = Self::new(texture);
+        if frame_width == 0 || frame_height == 0 {
+            return sheet;
+        }
+
+        // Overflowing sums never fit, and saturated steps stop the walk.
+        let fits = |start: u32, extent: u32, limit: u32| {
+            start.checked_add(extent).and_then(|end| end.checked_add(margin)).is_some_and(|end| end <= limit)
+        };
+        let (width, height) = (sheet.texture.width(), sheet.texture.height());
+        let mut y = margin;
+        while fits(y, frame_height, height) {
+            let mut x = margin;
+            while fits(x, frame_width, width) {
+                sheet.frames.push(TextureRegion::new(x, y, frame_width, frame_height));
+                x = x.saturating_add(frame_width).saturating_add(spacing);
+            }
+            y = y.saturating_add(frame_height).saturating_add(spacing);
+        }
+        sheet
+    }
+
+    pub fn add_frame(&mut self, name: &str, region: TextureRegion) -> usize {
+        let index = self.frames.len();
+        self.frames.push(region);
+        self.names.insert(name.to_string(), index);
+        index
+    }
+
+    pub fn name_frame(&mut self, name: &str, index: usize) {
+        if index < self.frames.len() {
+            self.names.insert(name.to_string(), index);
+        }
+    }
+
+    pub fn texture(&self) -> &Rc<Texture2D> {
+        &self.texture
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<TextureRegion> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    pub fn frame_by_name(&self, name: &str) -> Option<TextureRegion> {
+        self.index_of(name).and_then(|index| self.frame(index))
+    }
+
+    pub fn sprite(&self, index: usize, x: f32, y: f32, width: f32, height: f32) -> Option<Sprite> {
+        self.frame(index)
+            .map(|region| Sprite::new(x, y, width, height, Rc::clone(&self.texture), region))
+    }
+}
@@ -0,0 +1,301 @@
+use std::{collections::HashMap, rc::Rc};
+
+use ab_glyph::{Font as _, FontVec, GlyphId, PxScale, ScaleFont};
+
+use crate::error::{Error, Result};
+use crate::graphics::gl_wrapper::{max_texture_size, Texture2D, TextureOptions};
+use crate::graphics::renderer::{Material, Renderer2D, Vertex2D};
+use crate::logger::{LogLevel, Logger};
+
+const DEFAULT_ATLAS_SIZE: u32 = 1024;
+const GLYPH_PADDING: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GlyphEntry {
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    offset: [f32; 2],
+    size: [f32; 2],
+}
+
+struct GlyphAtlas {
+    texture: Rc<Texture2D>,
+    size: u32,
+    cursor_x: u32,
+    cursor_y: u32,
+    shelf_height: u32,
+    entries: HashMap<(GlyphId, u32), Option<GlyphEntry>>,
+}
+
+impl GlyphAtlas {
+    fn new(size: u32) -> Result<Self> {
+        let max = max_texture_size();
+        if size > max {
+            return Err(Error::TextureSize { width: size, height: size, max });
+        }
+        let pixels = vec![0u8; size as usize * size as usize * 4];
+        let texture = Texture2D::from_rgba(size, size, &pixels, TextureOptions::default())?;
+
+        Ok(GlyphAtlas {
+            texture: Rc::new(texture),
+            size,
+            cursor_x: GLYPH_PADDING,
+            cursor_y: GLYPH_PADDING,
+            shelf_height: 0,
+            entries: HashMap::new(),
+        })
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let end = |start: u32, extent: u32| start.saturating_add(extent).saturating_add(GLYPH_PADDING);
+        if end(self.cursor_x, width) > self.size {
+            self.cursor_x = GLYPH_PADDING;
+            self.cursor_y += self.shelf_height + GLYPH_PADDING;
+            self.shelf_height = 0;
+        }
+        if end(self.cursor_x, width) > self.size || end(self.cursor_y, height) > self.size {
+            return None;
+        }
+
+        let position = (self.cursor_x, self.cursor_y);
+        self.cursor_x += width + GLYPH_PADDING;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(position)
+    }
+}
+
+pub struct Font {
+    font: FontVec,
+    atlas: GlyphAtlas,
+}
+
+impl Font {
+    pub fn new(font_path: &str) -> Result<Self> {
+        let data = std::fs::read(font_path).map_err(|source| Error::FontLoad {
+            path: font_path.to_string(),
+            source,
+        })?;
+        Self::from_bytes(data)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        Self::with_atlas_size(data, DEFAULT_ATLAS_SIZE)
+    }
+
+    pub fn with_atlas_size(data: Vec<u8>, atlas_size: u32) -> Result<Self> {
+        let font = FontVec::try_from_vec(data).map_err(|_| Error::FontParse)?;
+        Ok(Font {
+            font,
+            atlas: GlyphAtlas::new(atlas_size)?,
+        })
+    }
+
+    pub fn texture(&self) -> Rc<Texture2D> {
+        Rc::clone(&self.atlas.texture)
+    }
+
+    pub fn line_height(&self, px_size: f32) -> f32 {
+        let scaled = self.font.as_scaled(PxScale::from(px_size));
+        scaled.height() + scaled.line_gap()
+    }
+
+    pub fn measure(&self, text: &str, px_size: f32) -> (f32, f32) {
+        let scaled = self.font.as_scaled(PxScale::from(px_size));
+        let width = text_lines(text)
+            .map(|line| self.line_width(line, px_size))
+            .fold(0.0, f32::max);
+        let line_count = text_lines(text).count() as f32;
+        let height = scaled.height() + (line_count - 1.0) * self.line_height(px_size);
+        (width, height)
+    }
+
+    fn line_width(&self, line: &str, px_size: f32) -> f32 {
+        let scaled = self.font.as_scaled(PxScale::from(px_size));
+        let mut caret = 0.0;
+        let mut previous: Option<GlyphId> = None;
+        for ch in line.chars() {
+            let id = scaled.glyph_id(ch);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        caret
+    }
+
+    fn glyph(&mut self, id: GlyphId, px_size: f32) -> Option<GlyphEntry> {
+        let key = (id, px_size.to_bits());
+        if let Some(entry) = self.atlas.entries.get(&key) {
+            return *entry;
+        }
+
+        let glyph = id.with_scale(PxScale::from(px_size));
+        let entry = self.font.outline_glyph(glyph).and_then(|outlined| {
+            let bounds = outlined.px_bounds();
+            let width = bounds.width() as u32;
+            let height = bounds.height() as u32;
+            let Some((x, y)) = self.atlas.allocate(width, height) else {
+                Logger::log(LogLevel::Warn, "Glyph atlas is full, glyph skipped");
+                return None;
+            };
+
+            let mut pixels = vec![0u8; width as usize * height as usize * 4];
+            outlined.draw(|gx, gy, coverage| {
+                if gx < width && gy < height {
+                    let index = (gy as usize * width as usize + gx as usize) * 4;
+                    pixels[index..index + 3].copy_from_slice(&[255, 255, 255]);
+                    pixels[index + 3] = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+                }
+            });
+            if let Err(error) = self.atlas.texture.update_sub_image(x, y, width, height, &pixels) {
+                Logger::log(LogLevel::Error, &error.to_string());
+                return None;
+            }
+
+            let atlas_size = self.atlas.size as f32;
+            Some(GlyphEntry {
+                uv_min: [x as f32 / atlas_size, y as f32 / atlas_size],
+                uv_max: [(x + width) as f32 / atlas_size, (y + height) as f32 / atlas_size],
+                offset: [bounds.min.x, bounds.min.y],
+                size: [width as f32, height as f32],
+            })
+        });
+
+        self.atlas.entries.insert(key, entry);
+        entry
+    }
+}
+
+// Splits on '\n' only, keeping a trailing empty line, and drops the '\r' of "\r\n" endings.
+fn text_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PlacedGlyph {
+    entry: GlyphEntry,
+    x: f32,
+    y: f32,
+}
+
+pub struct KText {
+    pub x: f32,
+    pub y: f32,
+    pub color: [f32; 4],
+    pub scale: [f32; 2],
+    px_size: f32,
+    align: TextAlign,
+    text: String,
+    texture: Rc<Texture2D>,
+    glyphs: Vec<PlacedGlyph>,
+    size: (f32, f32),
+}
+
+impl KText {
+    pub fn new(font: &mut Font, text: &str, x: f32, y: f32, px_size: f32, color: [f32; 4]) -> Self {
+        let mut k_text = Self {
+            x,
+            y,
+            color,
+            scale: [1.0, 1.0],
+            px_size,
+            align: TextAlign::Left,
+            text: String::new(),
+            texture: font.texture(),
+            glyphs: Vec::new(),
+            size: (0.0, 0.0),
+        };
+        k_text.set_text(font, text);
+        k_text
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, font: &mut Font, text: &str) {
+        self.text = text.to_string();
+        self.layout(font);
+    }
+
+    pub fn set_align(&mut self, font: &mut Font, align: TextAlign) {
+        self.align = align;
+        self.layout(font);
+    }
+
+    pub fn set_px_size(&mut self, font: &mut Font, px_size: f32) {
+        self.px_size = px_size;
+        self.layout(font);
+    }
+
+    pub fn measure(&self) -> (f32, f32) {
+        (self.size.0 * self.scale[0].abs(), self.size.1 * self.scale[1].abs())
+    }
+
+    fn layout(&mut self, font: &mut Font) {
+        self.texture = font.texture();
+        self.size = font.measure(&self.text, self.px_size);
+        self.glyphs.clear();
+
+        let scaled_font = font.font.as_scaled(PxScale::from(self.px_size));
+        let ascent = scaled_font.ascent();
+        let line_height = scaled_font.height() + scaled_font.line_gap();
+
+        for (line_index, line) in text_lines(&self.text).enumerate() {
+            let line_width = font.line_width(line, self.px_size);
+            let mut caret = match self.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => -line_width / 2.0,
+                TextAlign::Right => -line_width,
+            };
+            let baseline = ascent + line_index as f32 * line_height;
+
+            let mut previous: Option<GlyphId> = None;
+            for ch in line.chars() {
+                let (id, kern, advance) = {
+                    let scaled = font.font.as_scaled(PxScale::from(self.px_size));
+                    let id = scaled.glyph_id(ch);
+                    let kern = previous.map_or(0.0, |previous| scaled.kern(previous, id));
+                    (id, kern, scaled.h_advance(id))
+                };
+                caret += kern;
+                if let Some(entry) = font.glyph(id, self.px_size) {
+                    self.glyphs.push(PlacedGlyph { entry, x: caret, y: baseline });
+                }
+                caret += advance;
+                previous = Some(id);
+            }
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer2D) {
+        let scale_x = self.scale[0];
+        let scale_y = if renderer.is_y_down() { -self.scale[1] } else { self.scale[1] };
+        for glyph in &self.glyphs {
+            let entry = &glyph.entry;
+            let left = self.x + (glyph.x + entry.offset[0]) * scale_x;
+            let right = left + entry.size[0] * scale_x;
+            let top = self.y - (glyph.y + entry.offset[1]) * scale_y;
+            let bottom = top - entry.size[1] * scale_y;
+
+            let mut corners = [
+                Vertex2D::textured(right, top, entry.uv_max[0], entry.uv_min[1]),
+                Vertex2D::textured(right, bottom, entry.uv_max[0], entry.uv_max[1]),
+                Vertex2D::textured(left, bottom, entry.uv_min[0], entry.uv_max[1]),
+                Vertex2D::textured(left, top, entry.uv_min[0], entry.uv_min[1]),
+            ];
+            for corner in &mut corners {
+                corner.color = self.color;
+            }
+            renderer.submit_quad(Material::Texture(self.texture.id()), corners);
+        }
+    }
+}
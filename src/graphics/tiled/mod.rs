@@ -0,0 +1,560 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, rc::Rc};
+
+use cgmath::Vector2;
+
+use crate::{error::{Error, Result}, grid::CellCoord};
+use super::{
+    geometry::{collision::{Circle, Collider, ConvexPolygon, Segment}, triangulate::triangulate},
+    gl_wrapper::{Texture2D, TextureOptions},
+    sprite::SpriteSheet,
+    tilemap::{Tile, TileFlags, Tilemap},
+};
+
+mod tmj;
+mod tmx;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+const GID_MASK: u32 = !(FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL);
+const ELLIPSE_SEGMENTS: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Class(Properties),
+}
+
+pub type Properties = HashMap<String, Property>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileset {
+    pub first_gid: u32,
+    pub name: String,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub spacing: u32,
+    pub margin: u32,
+    pub columns: u32,
+    pub tile_count: u32,
+    pub image: Option<PathBuf>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    Rectangle { width: f32, height: f32 },
+    Ellipse { width: f32, height: f32 },
+    Point,
+    Polygon(Vec<Vector2<f32>>),
+    Polyline(Vec<Vector2<f32>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub visible: bool,
+    pub gid: Option<u32>,
+    pub shape: ObjectShape,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledTileLayer {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub visible: bool,
+    pub gids: Vec<u32>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledObjectLayer {
+    pub name: String,
+    pub visible: bool,
+    pub objects: Vec<TiledObject>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledLayer {
+    Tiles(TiledTileLayer),
+    Objects(TiledObjectLayer),
+}
+
+#[derive(Debug, Clone)]
+pub struct TiledCollider {
+    pub layer: String,
+    pub object_id: u32,
+    pub name: String,
+    pub class: String,
+    pub collider: Collider,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledMap {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tilesets: Vec<TiledTileset>,
+    pub layers: Vec<TiledLayer>,
+    pub properties: Properties,
+}
+
+impl TiledMap {
+    // Picks the parser from the extension: `.tmx` is XML, `.tmj` and `.json` are JSON.
+    pub fn load(path: &str) -> Result<TiledMap> {
+        let source = std::fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_string(), source })?;
+        match extension(Path::new(path)).as_str() {
+            "tmx" => tmx::parse_map(path, &source),
+            "tmj" | "json" => tmj::parse_map(path, &source),
+            other => Err(unsupported(path, &format!("map file extension '{}'", other))),
+        }
+    }
+
+    pub fn tile_layer(&self, name: &str) -> Option<&TiledTileLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            TiledLayer::Tiles(tiles) if tiles.name == name => Some(tiles),
+            _ => None,
+        })
+    }
+
+    pub fn object_layer(&self, name: &str) -> Option<&TiledObjectLayer> {
+        self.layers.iter().find_map(|layer| match layer {
+            TiledLayer::Objects(objects) if objects.name == name => Some(objects),
+            _ => None,
+        })
+    }
+
+    pub fn tileset_for_gid(&self, gid: u32) -> Option<&TiledTileset> {
+        let gid = gid & GID_MASK;
+        self.tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid && gid - tileset.first_gid < tileset.tile_count)
+            .max_by_key(|tileset| tileset.first_gid)
+    }
+
+    fn single_tileset(&self) -> Result<&TiledTileset> {
+        match self.tilesets.as_slice() {
+            [tileset] => Ok(tileset),
+            [] => Err(Error::MapFormat { path: self.path.clone(), message: "map has no tilesets".to_string() }),
+            _ => Err(unsupported(&self.path, "more than one tileset per tilemap")),
+        }
+    }
+
+    pub fn load_sheet(&self, options: TextureOptions) -> Result<SpriteSheet> {
+        let tileset = self.single_tileset()?;
+        let image_path = tileset
+            .image
+            .as_ref()
+            .ok_or_else(|| unsupported(&self.path, "image collection tilesets"))?;
+        let image = image::open(image_path).map_err(|source| Error::ImageLoad {
+            path: image_path.display().to_string(),
+            source,
+        })?;
+        let texture = Rc::new(Texture2D::from_image(&image, options));
+        Ok(SpriteSheet::from_grid(texture, tileset.tile_width, tileset.tile_height, tileset.spacing, tileset.margin))
+    }
+
+    pub fn to_tilemap(&self, sheet: SpriteSheet, tile_width: f32, tile_height: f32) -> Result<Tilemap> {
+        self.single_tileset()?;
+        let mut tilemap = Tilemap::new(self.width, self.height, tile_width, tile_height, sheet);
+
+        for layer in &self.layers {
+            let TiledLayer::Tiles(layer) = layer else {
+                continue;
+            };
+            let index = tilemap.add_layer(&layer.name);
+            tilemap.set_layer_visible(index, layer.visible);
+            for cell in CellCoord::row_major(layer.width.min(self.width), layer.height.min(self.height)) {
+                let gid = layer.gids[cell.row as usize * layer.width as usize + cell.col as usize];
+                if let Some(tile) = self.tile_for_gid(gid)? {
+                    tilemap.set_tile(index, cell, Some(tile));
+                }
+            }
+        }
+        Ok(tilemap)
+    }
+
+    // `None` for empty cells; gids outside every tileset are a format error.
+    fn tile_for_gid(&self, gid: u32) -> Result<Option<Tile>> {
+        if gid & GID_MASK == 0 {
+            return Ok(None);
+        }
+        if gid & ROTATED_HEXAGONAL != 0 {
+            return Err(unsupported(&self.path, "hexagonal tile rotation"));
+        }
+        let tileset = self
+            .tileset_for_gid(gid)
+            .ok_or_else(|| format_error(&self.path, format!("gid {} is outside every tileset", gid & GID_MASK)))?;
+        let mut flags = TileFlags::NONE;
+        if gid & FLIPPED_HORIZONTALLY != 0 {
+            flags = flags | TileFlags::FLIP_HORIZONTAL;
+        }
+        if gid & FLIPPED_VERTICALLY != 0 {
+            flags = flags | TileFlags::FLIP_VERTICAL;
+        }
+        if gid & FLIPPED_DIAGONALLY != 0 {
+            flags = flags | TileFlags::FLIP_DIAGONAL;
+        }
+        let local = ((gid & GID_MASK) - tileset.first_gid) as usize;
+        Ok(Some(Tile::new(local).with_flags(flags)))
+    }
+
+    // Object coordinates are converted from Tiled pixels into the tilemap's world space.
+    // Concave polygons are split into triangles and polylines into segments, so a single
+    // object can yield several colliders sharing its id.
+    pub fn colliders(&self, tilemap: &Tilemap) -> Vec<TiledCollider> {
+        let (world_tile_width, world_tile_height) = tilemap.tile_size();
+        let scale = Vector2::new(
+            world_tile_width / self.tile_width.max(1) as f32,
+            world_tile_height / self.tile_height.max(1) as f32,
+        );
+        let to_world = |point: Vector2<f32>| tilemap.transform().apply(Vector2::new(point.x * scale.x, point.y * scale.y));
+
+        let mut colliders = Vec::new();
+        for layer in &self.layers {
+            let TiledLayer::Objects(layer) = layer else {
+                continue;
+            };
+            for object in &layer.objects {
+                for collider in object_colliders(object, &to_world, scale) {
+                    colliders.push(TiledCollider {
+                        layer: layer.name.clone(),
+                        object_id: object.id,
+                        name: object.name.clone(),
+                        class: object.class.clone(),
+                        collider,
+                    });
+                }
+            }
+        }
+        colliders
+    }
+}
+
+fn object_colliders(
+    object: &TiledObject,
+    to_world: &dyn Fn(Vector2<f32>) -> Vector2<f32>,
+    scale: Vector2<f32>,
+) -> Vec<Collider> {
+    let (sin, cos) = object.rotation.to_radians().sin_cos();
+    let place = |local: Vector2<f32>| {
+        to_world(Vector2::new(
+            object.x + local.x * cos - local.y * sin,
+            object.y + local.x * sin + local.y * cos,
+        ))
+    };
+
+    match &object.shape {
+        ObjectShape::Point => Vec::new(),
+        ObjectShape::Rectangle { width, height } => {
+            let top = if object.gid.is_some() { -height } else { 0.0 };
+            let corners = [
+                Vector2::new(0.0, top),
+                Vector2::new(*width, top),
+                Vector2::new(*width, top + height),
+                Vector2::new(0.0, top + height),
+            ];
+            vec![Collider::Polygon(ConvexPolygon::new(corners.iter().map(|&corner| place(corner)).collect()))]
+        }
+        ObjectShape::Ellipse { width, height } => {
+            let radius = Vector2::new(width / 2.0, height / 2.0);
+            let center = place(radius);
+            if (width - height).abs() <= f32::EPSILON && (scale.x.abs() - scale.y.abs()).abs() <= f32::EPSILON {
+                return vec![Collider::Circle(Circle::new(center, radius.x * scale.x.abs()))];
+            }
+            let points = (0..ELLIPSE_SEGMENTS)
+                .map(|index| {
+                    let angle = index as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    place(Vector2::new(radius.x + radius.x * angle.cos(), radius.y + radius.y * angle.sin()))
+                })
+                .collect();
+            vec![Collider::Polygon(ConvexPolygon::new(points))]
+        }
+        ObjectShape::Polygon(points) => {
+            let points: Vec<Vector2<f32>> = points.iter().map(|&point| place(point)).collect();
+            if points.len() < 3 {
+                return Vec::new();
+            }
+            if let Some(polygon) = ConvexPolygon::try_new(points.clone()) {
+                return vec![Collider::Polygon(polygon)];
+            }
+            triangulate(&points, &[])
+                .chunks(3)
+                .map(|triangle| {
+                    Collider::Polygon(ConvexPolygon::new(triangle.iter().map(|&index| points[index as usize]).collect()))
+                })
+                .collect()
+        }
+        ObjectShape::Polyline(points) => points
+            .windows(2)
+            .map(|pair| Collider::Segment(Segment::new(place(pair[0]), place(pair[1]))))
+            .collect(),
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+fn unsupported(path: &str, feature: &str) -> Error {
+    Error::UnsupportedMapFeature { path: path.to_string(), feature: feature.to_string() }
+}
+
+fn format_error(path: &str, message: impl Into<String>) -> Error {
+    Error::MapFormat { path: path.to_string(), message: message.into() }
+}
+
+fn resolve(base: &str, relative: &str) -> PathBuf {
+    Path::new(base).parent().unwrap_or_else(|| Path::new("")).join(relative)
+}
+
+fn check_map_header(path: &str, orientation: &str, infinite: bool) -> Result<()> {
+    if infinite {
+        return Err(unsupported(path, "infinite maps"));
+    }
+    if orientation != "orthogonal" {
+        return Err(unsupported(path, &format!("{} orientation", orientation)));
+    }
+    Ok(())
+}
+
+// Layers are drawn unshifted, opaque and without parallax, so maps relying on anything else
+// are rejected instead of rendering in the wrong place.
+fn check_layer_placement(path: &str, offset: (f32, f32), parallax: (f32, f32), opacity: f32) -> Result<()> {
+    if offset != (0.0, 0.0) {
+        return Err(unsupported(path, "layer offsets"));
+    }
+    if parallax != (1.0, 1.0) {
+        return Err(unsupported(path, "layer parallax"));
+    }
+    if opacity < 1.0 {
+        return Err(unsupported(path, "layer opacity"));
+    }
+    Ok(())
+}
+
+fn layer_tile_count(path: &str, width: u32, height: u32) -> Result<usize> {
+    width
+        .checked_mul(height)
+        .map(|count| count as usize)
+        .ok_or_else(|| format_error(path, format!("layer size {}x{} is too large", width, height)))
+}
+
+// Tile data without compression: CSV or base64 encoded little-endian u32 gids.
+fn decode_tile_data(path: &str, data: &str, encoding: &str, compression: &str, expected: usize) -> Result<Vec<u32>> {
+    if !compression.is_empty() {
+        return Err(unsupported(path, &format!("{} compressed tile data", compression)));
+    }
+    let gids: Vec<u32> = match encoding {
+        "csv" => data
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse::<u32>().map_err(|_| format_error(path, format!("invalid gid '{}'", value))))
+            .collect::<Result<_>>()?,
+        "base64" => {
+            let bytes = decode_base64(data.trim()).ok_or_else(|| format_error(path, "invalid base64 tile data"))?;
+            bytes.chunks_exact(4).map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect()
+        }
+        other => return Err(unsupported(path, &format!("'{}' tile data encoding", other))),
+    };
+    if gids.len() != expected {
+        return Err(format_error(path, format!("layer has {} tiles, expected {}", gids.len(), expected)));
+    }
+    Ok(gids)
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let value = |byte: u8| -> Option<u32> {
+        match byte {
+            b'A'..=b'Z' => Some((byte - b'A') as u32),
+            b'a'..=b'z' => Some((byte - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((byte - b'0') as u32 + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    };
+
+    let symbols: Vec<u8> = input.bytes().filter(|byte| !byte.is_ascii_whitespace() && *byte != b'=').collect();
+    let mut output = Vec::with_capacity(symbols.len() * 3 / 4);
+    for chunk in symbols.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut buffer = 0u32;
+        for (index, &symbol) in chunk.iter().enumerate() {
+            buffer |= value(symbol)? << (18 - 6 * index);
+        }
+        let bytes = buffer.to_be_bytes();
+        output.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tmx(data: &str, header: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" {}>
+ <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+  <image source="terrain.png" width="32" height="32"/>
+ </tileset>
+ <layer id="1" name="ground" width="2" height="2">
+  {}
+ </layer>
+</map>"#,
+            header, data
+        )
+    }
+
+    fn tmj(layer: &str) -> String {
+        format!(
+            r#"{{"orientation": "orthogonal", "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16,
+              "tilesets": [{{"firstgid": 10, "name": "terrain", "tilewidth": 16, "tileheight": 16,
+                             "tilecount": 4, "columns": 2, "image": "terrain.png"}}],
+              "layers": [{}]}}"#,
+            layer
+        )
+    }
+
+    fn gids(map: &TiledMap) -> &[u32] {
+        &map.tile_layer("ground").unwrap().gids
+    }
+
+    #[test]
+    fn reads_csv_and_base64_tile_data() {
+        let csv = tmx(r#"<data encoding="csv">1,2,
+0,2147483652</data>"#, "");
+        let map = tmx::parse_map("map.tmx", &csv).unwrap();
+        assert_eq!(gids(&map), &[1, 2, 0, 0x8000_0004]);
+        assert_eq!(map.tilesets[0].tile_count, 4);
+        assert_eq!(map.tilesets[0].image, Some(PathBuf::from("terrain.png")));
+
+        let base64 = tmx(r#"<data encoding="base64">AQAAAAIAAAADAAAABAAAAA==</data>"#, "");
+        assert_eq!(gids(&tmx::parse_map("map.tmx", &base64).unwrap()), &[1, 2, 3, 4]);
+
+        let json = tmj(r#"{"type": "tilelayer", "name": "ground", "width": 2, "height": 2, "data": [10, 0, 13, 11]}"#);
+        let map = tmj::parse_map("map.tmj", &json).unwrap();
+        assert_eq!(gids(&map), &[10, 0, 13, 11]);
+        assert_eq!(map.tilesets[0].first_gid, 10);
+    }
+
+    #[test]
+    fn gids_become_local_tiles_with_flip_flags() {
+        let map = tmj::parse_map("map.tmj", &tmj(r#"{"type": "objectgroup", "name": "empty"}"#)).unwrap();
+        assert_eq!(map.tile_for_gid(0).unwrap(), None);
+        assert_eq!(map.tile_for_gid(FLIPPED_HORIZONTALLY).unwrap(), None);
+        assert_eq!(map.tile_for_gid(12).unwrap(), Some(Tile::new(2)));
+        let all = FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY;
+        let flags = TileFlags::FLIP_HORIZONTAL | TileFlags::FLIP_VERTICAL | TileFlags::FLIP_DIAGONAL;
+        assert_eq!(map.tile_for_gid(13 | all).unwrap(), Some(Tile::new(3).with_flags(flags)));
+        assert!(matches!(map.tile_for_gid(10 | ROTATED_HEXAGONAL), Err(Error::UnsupportedMapFeature { .. })));
+    }
+
+    #[test]
+    fn gids_outside_the_tileset_are_rejected() {
+        let map = tmj::parse_map("map.tmj", &tmj(r#"{"type": "objectgroup", "name": "empty"}"#)).unwrap();
+        for gid in [1, 9, 14, GID_MASK, 9 | FLIPPED_VERTICALLY] {
+            assert!(matches!(map.tile_for_gid(gid), Err(Error::MapFormat { .. })), "gid {}", gid);
+        }
+        assert!(map.tileset_for_gid(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn unsupported_data_is_reported() {
+        let infinite = tmx(r#"<data encoding="csv">1,2,3,4</data>"#, r#"infinite="1""#);
+        assert!(matches!(tmx::parse_map("map.tmx", &infinite), Err(Error::UnsupportedMapFeature { .. })));
+        let chunks = tmj(r#"{"type": "tilelayer", "name": "ground", "width": 2, "height": 2, "chunks": []}"#);
+        assert!(matches!(tmj::parse_map("map.tmj", &chunks), Err(Error::UnsupportedMapFeature { .. })));
+
+        let compressed = tmx(r#"<data encoding="base64" compression="zlib">eJxjZGBgYAAAAAUAAQ==</data>"#, "");
+        assert!(matches!(tmx::parse_map("map.tmx", &compressed), Err(Error::UnsupportedMapFeature { .. })));
+        let compressed = tmj(r#"{"type": "tilelayer", "name": "ground", "width": 2, "height": 2,
+                                 "encoding": "base64", "compression": "gzip", "data": "AAAA"}"#);
+        assert!(matches!(tmj::parse_map("map.tmj", &compressed), Err(Error::UnsupportedMapFeature { .. })));
+    }
+
+    #[test]
+    fn malformed_layers_are_format_errors() {
+        let short = tmx(r#"<data encoding="csv">1,2,3</data>"#, "");
+        assert!(matches!(tmx::parse_map("map.tmx", &short), Err(Error::MapFormat { .. })));
+        let huge = tmj(r#"{"type": "tilelayer", "name": "ground", "width": 65536, "height": 65536, "data": []}"#);
+        assert!(matches!(tmj::parse_map("map.tmj", &huge), Err(Error::MapFormat { .. })));
+        let huge = tmx(r#"<data encoding="csv">1</data>"#, "")
+            .replace(r#"name="ground" width="2" height="2""#, r#"name="ground" width="65536" height="65536""#);
+        assert!(matches!(tmx::parse_map("map.tmx", &huge), Err(Error::MapFormat { .. })));
+    }
+
+    #[test]
+    fn hidden_groups_hide_their_children() {
+        let json = tmj(r#"{"type": "group", "name": "decor", "visible": false, "layers": [
+                            {"type": "tilelayer", "name": "ground", "width": 2, "height": 2, "data": [0, 0, 0, 0]},
+                            {"type": "objectgroup", "name": "props", "objects": []}]}"#);
+        let map = tmj::parse_map("map.tmj", &json).unwrap();
+        assert!(!map.tile_layer("ground").unwrap().visible);
+        assert!(!map.object_layer("props").unwrap().visible);
+
+        let xml = tmx(r#"<data encoding="csv">0,0,0,0</data>"#, "")
+            .replace(r#" <layer id="1""#, r#" <group name="decor" visible="0"><layer id="1""#)
+            .replace(" </layer>", " </layer></group>");
+        assert!(!tmx::parse_map("map.tmx", &xml).unwrap().tile_layer("ground").unwrap().visible);
+    }
+
+    #[test]
+    fn layer_offsets_and_templates_are_unsupported() {
+        let shifted = tmx(r#"<data encoding="csv">0,0,0,0</data>"#, "")
+            .replace(r#"name="ground""#, r#"name="ground" offsetx="8""#);
+        assert!(matches!(tmx::parse_map("map.tmx", &shifted), Err(Error::UnsupportedMapFeature { .. })));
+        let shifted = tmj(r#"{"type": "objectgroup", "name": "props", "offsety": -4, "objects": []}"#);
+        assert!(matches!(tmj::parse_map("map.tmj", &shifted), Err(Error::UnsupportedMapFeature { .. })));
+
+        let template = tmj(r#"{"type": "objectgroup", "name": "props",
+                               "objects": [{"id": 1, "template": "chest.tj", "x": 0, "y": 0}]}"#);
+        assert!(matches!(tmj::parse_map("map.tmj", &template), Err(Error::UnsupportedMapFeature { .. })));
+        let template = tmx(r#"<data encoding="csv">0,0,0,0</data>"#, "").replace(
+            "</map>",
+            r#"<objectgroup name="props"><object id="1" template="chest.tx" x="0" y="0"/></objectgroup></map>"#,
+        );
+        assert!(matches!(tmx::parse_map("map.tmx", &template), Err(Error::UnsupportedMapFeature { .. })));
+    }
+
+    #[test]
+    fn text_objects_are_skipped() {
+        let json = tmj(r#"{"type": "objectgroup", "name": "props", "objects": [
+                            {"id": 1, "x": 0, "y": 0, "width": 16, "height": 16, "text": {"text": "hi"}},
+                            {"id": 2, "x": 0, "y": 0, "width": 16, "height": 16}]}"#);
+        let map = tmj::parse_map("map.tmj", &json).unwrap();
+        let objects = &map.object_layer("props").unwrap().objects;
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].id, 2);
+
+        let xml = tmx(r#"<data encoding="csv">0,0,0,0</data>"#, "").replace(
+            "</map>",
+            r#"<objectgroup name="props"><object id="1" x="0" y="0" width="16" height="16"><text>hi</text></object>
+               <object id="2" x="0" y="0" width="16" height="16"/></objectgroup></map>"#,
+        );
+        let map = tmx::parse_map("map.tmx", &xml).unwrap();
+        assert_eq!(map.object_layer("props").unwrap().objects.len(), 1);
+    }
+}
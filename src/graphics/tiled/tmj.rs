@@ -0,0 +1,294 @@
+use cgmath::Vector2;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use super::{
+    check_layer_placement, check_map_header, decode_tile_data, extension, format_error, layer_tile_count, resolve, unsupported, ObjectShape,
+    Properties, Property, TiledLayer, TiledMap, TiledObject, TiledObjectLayer, TiledTileLayer, TiledTileset,
+};
+
+struct Fields<'a> {
+    path: &'a str,
+    context: &'a str,
+    object: &'a Map<String, Value>,
+}
+
+impl<'a> Fields<'a> {
+    fn of(path: &'a str, context: &'a str, value: &'a Value) -> Result<Self> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| format_error(path, format!("{} must be a JSON object", context)))?;
+        Ok(Self { path, context, object })
+    }
+
+    fn get(&self, name: &str) -> Option<&'a Value> {
+        self.object.get(name)
+    }
+
+    fn string(&self, name: &str) -> String {
+        self.get(name).and_then(Value::as_str).unwrap_or_default().to_string()
+    }
+
+    fn boolean(&self, name: &str, default: bool) -> bool {
+        self.get(name).and_then(Value::as_bool).unwrap_or(default)
+    }
+
+    fn float(&self, name: &str) -> f32 {
+        self.float_or(name, 0.0)
+    }
+
+    fn float_or(&self, name: &str, default: f32) -> f32 {
+        self.get(name).and_then(Value::as_f64).map_or(default, |value| value as f32)
+    }
+
+    fn unsigned(&self, name: &str, default: u32) -> Result<u32> {
+        match self.get(name) {
+            None | Some(Value::Null) => Ok(default),
+            Some(value) => value
+                .as_u64()
+                .and_then(|value| u32::try_from(value).ok())
+                .ok_or_else(|| self.invalid(name)),
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<u32> {
+        if self.get(name).is_none() {
+            return Err(format_error(self.path, format!("{} is missing '{}'", self.context, name)));
+        }
+        self.unsigned(name, 0)
+    }
+
+    fn array(&self, name: &str) -> &'a [Value] {
+        self.get(name).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn invalid(&self, name: &str) -> Error {
+        format_error(self.path, format!("{} has an invalid '{}'", self.context, name))
+    }
+}
+
+fn parse_json(path: &str, source: &str) -> Result<Value> {
+    serde_json::from_str(source).map_err(|source| Error::Json { path: path.to_string(), source })
+}
+
+pub(super) fn parse_map(path: &str, source: &str) -> Result<TiledMap> {
+    let document = parse_json(path, source)?;
+    let map = Fields::of(path, "map", &document)?;
+    let orientation = map.string("orientation");
+    let orientation = if orientation.is_empty() { "orthogonal".to_string() } else { orientation };
+    check_map_header(path, &orientation, map.boolean("infinite", false))?;
+
+    let tilesets = map
+        .array("tilesets")
+        .iter()
+        .map(|tileset| parse_tileset_reference(path, tileset))
+        .collect::<Result<Vec<_>>>()?;
+    let mut layers = Vec::new();
+    for layer in map.array("layers") {
+        parse_layer(path, layer, true, &mut layers)?;
+    }
+
+    Ok(TiledMap {
+        path: path.to_string(),
+        width: map.required("width")?,
+        height: map.required("height")?,
+        tile_width: map.required("tilewidth")?,
+        tile_height: map.required("tileheight")?,
+        tilesets,
+        layers,
+        properties: parse_properties(path, map.array("properties"))?,
+    })
+}
+
+pub(super) fn parse_external_tileset(path: &str, source: &str, first_gid: u32) -> Result<TiledTileset> {
+    let document = parse_json(path, source)?;
+    parse_tileset(path, &document, first_gid)
+}
+
+fn parse_tileset_reference(path: &str, value: &Value) -> Result<TiledTileset> {
+    let fields = Fields::of(path, "tileset", value)?;
+    let first_gid = fields.required("firstgid")?;
+    let source = fields.string("source");
+    if source.is_empty() {
+        return parse_tileset(path, value, first_gid);
+    }
+
+    let tileset_path = resolve(path, &source);
+    let tileset_name = tileset_path.display().to_string();
+    let contents = std::fs::read_to_string(&tileset_path).map_err(|source| Error::Io { path: tileset_name.clone(), source })?;
+    match extension(&tileset_path).as_str() {
+        "tsj" | "json" => parse_external_tileset(&tileset_name, &contents, first_gid),
+        "tsx" => super::tmx::parse_external_tileset(&tileset_name, &contents, first_gid),
+        other => Err(unsupported(&tileset_name, &format!("tileset file extension '{}'", other))),
+    }
+}
+
+fn parse_tileset(path: &str, value: &Value, first_gid: u32) -> Result<TiledTileset> {
+    let fields = Fields::of(path, "tileset", value)?;
+    let image = fields.string("image");
+    Ok(TiledTileset {
+        first_gid,
+        name: fields.string("name"),
+        tile_width: fields.required("tilewidth")?,
+        tile_height: fields.required("tileheight")?,
+        spacing: fields.unsigned("spacing", 0)?,
+        margin: fields.unsigned("margin", 0)?,
+        columns: fields.unsigned("columns", 0)?,
+        tile_count: fields.unsigned("tilecount", 0)?,
+        image: (!image.is_empty()).then(|| resolve(path, &image)),
+        properties: parse_properties(path, fields.array("properties"))?,
+    })
+}
+
+// Children of a hidden group are hidden too.
+fn parse_layer(path: &str, value: &Value, parent_visible: bool, layers: &mut Vec<TiledLayer>) -> Result<()> {
+    let fields = Fields::of(path, "layer", value)?;
+    check_layer_placement(
+        path,
+        (fields.float("offsetx"), fields.float("offsety")),
+        (fields.float_or("parallaxx", 1.0), fields.float_or("parallaxy", 1.0)),
+        fields.float_or("opacity", 1.0),
+    )?;
+    let visible = parent_visible && fields.boolean("visible", true);
+    match fields.string("type").as_str() {
+        "tilelayer" => {
+            if fields.get("chunks").is_some() {
+                return Err(unsupported(path, "infinite maps"));
+            }
+            let width = fields.required("width")?;
+            let height = fields.required("height")?;
+            let expected = layer_tile_count(path, width, height)?;
+            let gids = match fields.get("data") {
+                Some(Value::String(data)) => {
+                    let encoding = fields.string("encoding");
+                    decode_tile_data(path, data, &encoding, &fields.string("compression"), expected)?
+                }
+                Some(Value::Array(data)) => {
+                    let gids = data
+                        .iter()
+                        .map(|gid| gid.as_u64().and_then(|gid| u32::try_from(gid).ok()).ok_or_else(|| fields.invalid("data")))
+                        .collect::<Result<Vec<u32>>>()?;
+                    if gids.len() != expected {
+                        return Err(format_error(path, format!("layer has {} tiles, expected {}", gids.len(), expected)));
+                    }
+                    gids
+                }
+                _ => return Err(fields.invalid("data")),
+            };
+            layers.push(TiledLayer::Tiles(TiledTileLayer {
+                name: fields.string("name"),
+                width,
+                height,
+                visible,
+                gids,
+                properties: parse_properties(path, fields.array("properties"))?,
+            }));
+        }
+        "objectgroup" => {
+            let objects = fields
+                .array("objects")
+                .iter()
+                .filter_map(|object| parse_object(path, object).transpose())
+                .collect::<Result<Vec<_>>>()?;
+            layers.push(TiledLayer::Objects(TiledObjectLayer {
+                name: fields.string("name"),
+                visible,
+                objects,
+                properties: parse_properties(path, fields.array("properties"))?,
+            }));
+        }
+        "group" => {
+            for layer in fields.array("layers") {
+                parse_layer(path, layer, visible, layers)?;
+            }
+        }
+        "imagelayer" => return Err(unsupported(path, "image layers")),
+        other => return Err(format_error(path, format!("unknown layer type '{}'", other))),
+    }
+    Ok(())
+}
+
+fn parse_points(path: &str, values: &[Value]) -> Result<Vec<Vector2<f32>>> {
+    values
+        .iter()
+        .map(|value| {
+            let point = Fields::of(path, "point", value)?;
+            Ok(Vector2::new(point.float("x"), point.float("y")))
+        })
+        .collect()
+}
+
+// Text objects have no shape to collide with and are skipped.
+fn parse_object(path: &str, value: &Value) -> Result<Option<TiledObject>> {
+    let fields = Fields::of(path, "object", value)?;
+    if fields.get("template").is_some() {
+        return Err(unsupported(path, "object templates"));
+    }
+    if fields.get("text").is_some() {
+        return Ok(None);
+    }
+    let width = fields.float("width");
+    let height = fields.float("height");
+    let shape = if fields.boolean("ellipse", false) {
+        ObjectShape::Ellipse { width, height }
+    } else if fields.boolean("point", false) {
+        ObjectShape::Point
+    } else if fields.get("polygon").is_some() {
+        ObjectShape::Polygon(parse_points(path, fields.array("polygon"))?)
+    } else if fields.get("polyline").is_some() {
+        ObjectShape::Polyline(parse_points(path, fields.array("polyline"))?)
+    } else {
+        ObjectShape::Rectangle { width, height }
+    };
+    let class = fields.string("class");
+
+    Ok(Some(TiledObject {
+        id: fields.unsigned("id", 0)?,
+        name: fields.string("name"),
+        class: if class.is_empty() { fields.string("type") } else { class },
+        x: fields.float("x"),
+        y: fields.float("y"),
+        rotation: fields.float("rotation"),
+        visible: fields.boolean("visible", true),
+        gid: fields.get("gid").map(|_| fields.unsigned("gid", 0)).transpose()?,
+        shape,
+        properties: parse_properties(path, fields.array("properties"))?,
+    }))
+}
+
+fn parse_properties(path: &str, values: &[Value]) -> Result<Properties> {
+    let mut properties = Properties::new();
+    for value in values {
+        let fields = Fields::of(path, "property", value)?;
+        let name = fields.string("name");
+        let raw = fields.get("value").unwrap_or(&Value::Null);
+        let property = match fields.string("type").as_str() {
+            "bool" => Property::Bool(raw.as_bool().ok_or_else(|| fields.invalid("value"))?),
+            "int" | "object" => Property::Int(raw.as_i64().ok_or_else(|| fields.invalid("value"))?),
+            "float" => Property::Float(raw.as_f64().ok_or_else(|| fields.invalid("value"))?),
+            "class" => inferred_property(raw),
+            _ => Property::String(raw.as_str().unwrap_or_default().to_string()),
+        };
+        properties.insert(name, property);
+    }
+    Ok(properties)
+}
+
+// Class members in TMJ carry no type tags, so they are typed from the JSON value itself.
+fn inferred_property(value: &Value) -> Property {
+    match value {
+        Value::Bool(value) => Property::Bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => Property::Int(value),
+            None => Property::Float(number.as_f64().unwrap_or_default()),
+        },
+        Value::Object(members) => Property::Class(
+            members
+                .iter()
+                .map(|(name, member)| (name.clone(), inferred_property(member)))
+                .collect(),
+        ),
+        Value::String(value) => Property::String(value.clone()),
+        _ => Property::String(String::new()),
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use cgmath::Vector2;
+use quick_xml::{events::{BytesStart, Event}, Reader};
+
+use crate::error::{Error, Result};
+use super::{
+    check_layer_placement, check_map_header, decode_tile_data, extension, format_error, layer_tile_count, resolve, unsupported, ObjectShape,
+    Properties, Property, TiledLayer, TiledMap, TiledObject, TiledObjectLayer, TiledTileLayer, TiledTileset,
+};
+
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    fn text_attribute(&self, name: &str) -> String {
+        self.attribute(name).unwrap_or_default().to_string()
+    }
+
+    fn number<T: std::str::FromStr>(&self, path: &str, name: &str, default: T) -> Result<T> {
+        match self.attribute(name) {
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| format_error(path, format!("<{}> has invalid {}='{}'", self.name, name, value))),
+            None => Ok(default),
+        }
+    }
+
+    fn required<T: std::str::FromStr>(&self, path: &str, name: &str) -> Result<T> {
+        let value = self
+            .attribute(name)
+            .ok_or_else(|| format_error(path, format!("<{}> is missing '{}'", self.name, name)))?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| format_error(path, format!("<{}> has invalid {}='{}'", self.name, name, value)))
+    }
+
+    fn flag(&self, name: &str, default: bool) -> bool {
+        self.attribute(name).map(|value| value != "0" && value != "false").unwrap_or(default)
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+fn start_element(path: &str, start: &BytesStart) -> Result<Element> {
+    let mut attributes = HashMap::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|error| format_error(path, error.to_string()))?;
+        let value = attribute.unescape_value().map_err(|error| format_error(path, error.to_string()))?;
+        attributes.insert(String::from_utf8_lossy(attribute.key.as_ref()).into_owned(), value.into_owned());
+    }
+    Ok(Element {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        attributes,
+        children: Vec::new(),
+        text: String::new(),
+    })
+}
+
+fn parse_document(path: &str, source: &str) -> Result<Element> {
+    let mut reader = Reader::from_str(source);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        let event = reader.read_event().map_err(|error| {
+            format_error(path, format!("XML error at byte {}: {}", reader.buffer_position(), error))
+        })?;
+        match event {
+            Event::Start(start) => stack.push(start_element(path, &start)?),
+            Event::Empty(start) => {
+                let element = start_element(path, &start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| format_error(path, "unbalanced XML end tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    let text = text.unescape().map_err(|error| format_error(path, error.to_string()))?;
+                    element.text.push_str(&text);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::Eof => return Err(format_error(path, "unexpected end of XML document")),
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn parse_map(path: &str, source: &str) -> Result<TiledMap> {
+    let root = parse_document(path, source)?;
+    if root.name != "map" {
+        return Err(format_error(path, format!("expected <map>, found <{}>", root.name)));
+    }
+    check_map_header(path, root.attribute("orientation").unwrap_or("orthogonal"), root.flag("infinite", false))?;
+
+    let width: u32 = root.required(path, "width")?;
+    let height: u32 = root.required(path, "height")?;
+    let mut tilesets = Vec::new();
+    let mut layers = Vec::new();
+    for child in &root.children {
+        match child.name.as_str() {
+            "tileset" => tilesets.push(parse_tileset_reference(path, child)?),
+            "layer" | "objectgroup" | "group" | "imagelayer" => parse_layer(path, child, true, &mut layers)?,
+            _ => {}
+        }
+    }
+
+    Ok(TiledMap {
+        path: path.to_string(),
+        width,
+        height,
+        tile_width: root.required(path, "tilewidth")?,
+        tile_height: root.required(path, "tileheight")?,
+        tilesets,
+        layers,
+        properties: parse_properties(path, &root)?,
+    })
+}
+
+fn parse_tileset_reference(path: &str, element: &Element) -> Result<TiledTileset> {
+    let first_gid: u32 = element.required(path, "firstgid")?;
+    let Some(source) = element.attribute("source") else {
+        return parse_tileset(path, element, first_gid);
+    };
+
+    let tileset_path = resolve(path, source);
+    let tileset_name = tileset_path.display().to_string();
+    let contents = std::fs::read_to_string(&tileset_path).map_err(|source| Error::Io { path: tileset_name.clone(), source })?;
+    match extension(&tileset_path).as_str() {
+        "tsx" => parse_external_tileset(&tileset_name, &contents, first_gid),
+        "tsj" | "json" => super::tmj::parse_external_tileset(&tileset_name, &contents, first_gid),
+        other => Err(unsupported(&tileset_name, &format!("tileset file extension '{}'", other))),
+    }
+}
+
+pub(super) fn parse_external_tileset(path: &str, source: &str, first_gid: u32) -> Result<TiledTileset> {
+    let root = parse_document(path, source)?;
+    if root.name != "tileset" {
+        return Err(format_error(path, format!("expected <tileset>, found <{}>", root.name)));
+    }
+    parse_tileset(path, &root, first_gid)
+}
+
+// `path` is the file the tileset lives in, used to resolve its image relative to it.
+fn parse_tileset(path: &str, element: &Element, first_gid: u32) -> Result<TiledTileset> {
+    let tile_width: u32 = element.required(path, "tilewidth")?;
+    let tile_height: u32 = element.required(path, "tileheight")?;
+    let image = element.child("image");
+    let image_path = image.and_then(|image| image.attribute("source")).map(|source| resolve(path, source));
+    let columns = element.number(path, "columns", 0)?;
+    let tile_count = element.number(path, "tilecount", 0)?;
+
+    Ok(TiledTileset {
+        first_gid,
+        name: element.text_attribute("name"),
+        tile_width,
+        tile_height,
+        spacing: element.number(path, "spacing", 0)?,
+        margin: element.number(path, "margin", 0)?,
+        columns,
+        tile_count,
+        image: image_path,
+        properties: parse_properties(path, element)?,
+    })
+}
+
+// Children of a hidden group are hidden too.
+fn parse_layer(path: &str, element: &Element, parent_visible: bool, layers: &mut Vec<TiledLayer>) -> Result<()> {
+    check_layer_placement(
+        path,
+        (element.number(path, "offsetx", 0.0)?, element.number(path, "offsety", 0.0)?),
+        (element.number(path, "parallaxx", 1.0)?, element.number(path, "parallaxy", 1.0)?),
+        element.number(path, "opacity", 1.0)?,
+    )?;
+    let visible = parent_visible && element.flag("visible", true);
+    match element.name.as_str() {
+        "layer" => {
+            let width: u32 = element.required(path, "width")?;
+            let height: u32 = element.required(path, "height")?;
+            let data = element
+                .child("data")
+                .ok_or_else(|| format_error(path, format!("layer '{}' has no <data>", element.text_attribute("name"))))?;
+            if data.child("chunk").is_some() {
+                return Err(unsupported(path, "infinite maps"));
+            }
+            let expected = layer_tile_count(path, width, height)?;
+            let gids = match data.attribute("encoding") {
+                Some(encoding) => {
+                    decode_tile_data(path, &data.text, encoding, data.attribute("compression").unwrap_or(""), expected)?
+                }
+                None => {
+                    let gids = data
+                        .children
+                        .iter()
+                        .filter(|child| child.name == "tile")
+                        .map(|tile| tile.number(path, "gid", 0u32))
+                        .collect::<Result<Vec<u32>>>()?;
+                    if gids.len() != expected {
+                        return Err(format_error(path, format!("layer has {} tiles, expected {}", gids.len(), expected)));
+                    }
+                    gids
+                }
+            };
+            layers.push(TiledLayer::Tiles(TiledTileLayer {
+                name: element.text_attribute("name"),
+                width,
+                height,
+                visible,
+                gids,
+                properties: parse_properties(path, element)?,
+            }));
+        }
+        "objectgroup" => {
+            let objects = element
+                .children
+                .iter()
+                .filter(|child| child.name == "object")
+                .filter_map(|object| parse_object(path, object).transpose())
+                .collect::<Result<Vec<_>>>()?;
+            layers.push(TiledLayer::Objects(TiledObjectLayer {
+                name: element.text_attribute("name"),
+                visible,
+                objects,
+                properties: parse_properties(path, element)?,
+            }));
+        }
+        "group" => {
+            for child in &element.children {
+                parse_layer(path, child, visible, layers)?;
+            }
+        }
+        "imagelayer" => return Err(unsupported(path, "image layers")),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse_points(path: &str, points: &str) -> Result<Vec<Vector2<f32>>> {
+    points
+        .split_whitespace()
+        .map(|pair| {
+            let (x, y) = pair.split_once(',').ok_or_else(|| format_error(path, format!("invalid point '{}'", pair)))?;
+            match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Ok(Vector2::new(x, y)),
+                _ => Err(format_error(path, format!("invalid point '{}'", pair))),
+            }
+        })
+        .collect()
+}
+
+// Text objects have no shape to collide with and are skipped.
+fn parse_object(path: &str, element: &Element) -> Result<Option<TiledObject>> {
+    if element.attribute("template").is_some() {
+        return Err(unsupported(path, "object templates"));
+    }
+    if element.child("text").is_some() {
+        return Ok(None);
+    }
+    let width = element.number(path, "width", 0.0)?;
+    let height = element.number(path, "height", 0.0)?;
+    let shape = if element.child("ellipse").is_some() {
+        ObjectShape::Ellipse { width, height }
+    } else if element.child("point").is_some() {
+        ObjectShape::Point
+    } else if let Some(polygon) = element.child("polygon") {
+        ObjectShape::Polygon(parse_points(path, polygon.attribute("points").unwrap_or(""))?)
+    } else if let Some(polyline) = element.child("polyline") {
+        ObjectShape::Polyline(parse_points(path, polyline.attribute("points").unwrap_or(""))?)
+    } else {
+        ObjectShape::Rectangle { width, height }
+    };
+
+    Ok(Some(TiledObject {
+        id: element.number(path, "id", 0)?,
+        name: element.text_attribute("name"),
+        class: element.attribute("class").or(element.attribute("type")).unwrap_or_default().to_string(),
+        x: element.number(path, "x", 0.0)?,
+        y: element.number(path, "y", 0.0)?,
+        rotation: element.number(path, "rotation", 0.0)?,
+        visible: element.flag("visible", true),
+        gid: element.attribute("gid").map(|_| element.required(path, "gid")).transpose()?,
+        shape,
+        properties: parse_properties(path, element)?,
+    }))
+}
+
+fn parse_properties(path: &str, element: &Element) -> Result<Properties> {
+    let mut properties = Properties::new();
+    let Some(list) = element.child("properties") else {
+        return Ok(properties);
+    };
+
+    for property in list.children.iter().filter(|child| child.name == "property") {
+        let name = property.text_attribute("name");
+        let value = property.attribute("value").map(str::to_string).unwrap_or_else(|| property.text.clone());
+        let invalid = || format_error(path, format!("property '{}' has invalid value '{}'", name, value));
+        let parsed = match property.attribute("type").unwrap_or("string") {
+            "bool" => Property::Bool(value == "true"),
+            "int" | "object" => Property::Int(if value.is_empty() { 0 } else { value.parse().map_err(|_| invalid())? }),
+            "float" => Property::Float(value.parse().map_err(|_| invalid())?),
+            "class" => Property::Class(parse_properties(path, property)?),
+            _ => Property::String(value.clone()),
+        };
+        properties.insert(name, parsed);
+    }
+    Ok(properties)
+}
@@ -0,0 +1,340 @@
+use std::ops::BitOr;
+
+use cgmath::{Matrix4, SquareMatrix, Vector2, Vector4};
+
+use crate::grid::{CellCoord, Grid};
+
+use super::{
+    geometry::image::textured_quad_corners,
+    renderer::{Material, Primitive, Renderer2D, StaticMesh, Vertex2D, QUAD_INDICES},
+    sprite::SpriteSheet,
+    transform::Transform2D,
+    window::Window,
+};
+
+const DEFAULT_CHUNK_SIZE: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TileFlags(u8);
+
+impl TileFlags {
+    pub const NONE: TileFlags = TileFlags(0);
+    pub const FLIP_HORIZONTAL: TileFlags = TileFlags(1);
+    pub const FLIP_VERTICAL: TileFlags = TileFlags(2);
+    pub const FLIP_DIAGONAL: TileFlags = TileFlags(4);
+
+    // Clockwise quarter turns expressed with the same flip bits Tiled uses.
+    pub fn rotation(quarter_turns: u32) -> TileFlags {
+        match quarter_turns % 4 {
+            1 => TileFlags::FLIP_DIAGONAL | TileFlags::FLIP_HORIZONTAL,
+            2 => TileFlags::FLIP_HORIZONTAL | TileFlags::FLIP_VERTICAL,
+            3 => TileFlags::FLIP_DIAGONAL | TileFlags::FLIP_VERTICAL,
+            _ => TileFlags::NONE,
+        }
+    }
+
+    pub fn contains(self, other: TileFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl BitOr for TileFlags {
+    type Output = TileFlags;
+
+    fn bitor(self, other: TileFlags) -> TileFlags {
+        TileFlags(self.0 | other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub index: usize,
+    pub flags: TileFlags,
+}
+
+impl Tile {
+    pub fn new(index: usize) -> Self {
+        Self { index, flags: TileFlags::NONE }
+    }
+
+    pub fn with_flags(mut self, flags: TileFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+}
+
+struct Chunk {
+    mesh: Option<StaticMesh>,
+    dirty: bool,
+}
+
+pub struct TileLayer {
+    pub name: String,
+    pub visible: bool,
+    tiles: Vec<Option<Tile>>,
+    chunks: Vec<Chunk>,
+}
+
+impl TileLayer {
+    pub fn tiles(&self) -> &[Option<Tile>] {
+        &self.tiles
+    }
+}
+
+pub struct Tilemap {
+    cols: u32,
+    rows: u32,
+    tile_width: f32,
+    tile_height: f32,
+    chunk_size: u32,
+    sheet: SpriteSheet,
+    layers: Vec<TileLayer>,
+    transform: Transform2D,
+    built_transform: Option<Transform2D>,
+}
+
+impl Tilemap {
+    // Cell (0, 0) is the top-left cell; rows grow downwards in the map's local space.
+    pub fn new(cols: u32, rows: u32, tile_width: f32, tile_height: f32, sheet: SpriteSheet) -> Self {
+        Self {
+            cols,
+            rows,
+            tile_width,
+            tile_height,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            sheet,
+            layers: Vec::new(),
+            transform: Transform2D::default(),
+            built_transform: None,
+        }
+    }
+
+    // Lays the map over `grid` so that tile (col, row) covers the grid cell of the same coordinate.
+    pub fn for_grid(grid: &Grid, sheet: SpriteSheet) -> Self {
+        let mut tilemap = Self::new(grid.cols, grid.rows, grid.cell_width, grid.cell_height, sheet);
+        let flip = if grid.flip_y { -1.0 } else { 1.0 };
+        tilemap.set_transform(Transform2D::new(grid.origin.x, grid.origin.y).with_scale(1.0, flip));
+        tilemap
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        let chunk_count = self.chunk_count();
+        for layer in &mut self.layers {
+            layer.chunks = (0..chunk_count).map(|_| Chunk { mesh: None, dirty: true }).collect();
+        }
+        self
+    }
+
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn tile_size(&self) -> (f32, f32) {
+        (self.tile_width, self.tile_height)
+    }
+
+    pub fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    pub fn transform(&self) -> &Transform2D {
+        &self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Transform2D) {
+        self.transform = transform;
+    }
+
+    fn chunks_x(&self) -> u32 {
+        self.cols.div_ceil(self.chunk_size)
+    }
+
+    fn chunk_count(&self) -> usize {
+        (self.chunks_x() * self.rows.div_ceil(self.chunk_size)) as usize
+    }
+
+    fn chunk_index(&self, col: u32, row: u32) -> usize {
+        ((row / self.chunk_size) * self.chunks_x() + col / self.chunk_size) as usize
+    }
+
+    pub fn add_layer(&mut self, name: &str) -> usize {
+        self.layers.push(TileLayer {
+            name: name.to_string(),
+            visible: true,
+            tiles: vec![None; (self.cols * self.rows) as usize],
+            chunks: (0..self.chunk_count()).map(|_| Chunk { mesh: None, dirty: true }).collect(),
+        });
+        self.layers.len() - 1
+    }
+
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    pub fn set_layer_visible(&mut self, layer: usize, visible: bool) {
+        if let Some(layer) = self.layers.get_mut(layer) {
+            layer.visible = visible;
+        }
+    }
+
+    pub fn in_bounds(&self, cell: CellCoord) -> bool {
+        cell.is_within(self.cols, self.rows)
+    }
+
+    pub fn tile(&self, layer: usize, cell: CellCoord) -> Option<Tile> {
+        let index = cell.index_within(self.cols, self.rows)?;
+        self.layers.get(layer)?.tiles[index]
+    }
+
+    pub fn set_tile(&mut self, layer: usize, cell: CellCoord, tile: Option<Tile>) {
+        let Some(index) = cell.index_within(self.cols, self.rows) else {
+            return;
+        };
+        let chunk = self.chunk_index(cell.col as u32, cell.row as u32);
+        if let Some(layer) = self.layers.get_mut(layer) {
+            if layer.tiles[index] != tile {
+                layer.tiles[index] = tile;
+                layer.chunks[chunk].dirty = true;
+            }
+        }
+    }
+
+    pub fn fill(&mut self, layer: usize, tile: Option<Tile>) {
+        if let Some(layer) = self.layers.get_mut(layer) {
+            layer.tiles.iter_mut().for_each(|cell| *cell = tile);
+            layer.chunks.iter_mut().for_each(|chunk| chunk.dirty = true);
+        }
+    }
+
+    pub fn cell_center(&self, cell: CellCoord) -> Vector2<f32> {
+        self.transform.apply(Vector2::new(
+            (cell.col as f32 + 0.5) * self.tile_width,
+            (cell.row as f32 + 0.5) * self.tile_height,
+        ))
+    }
+
+    pub fn cell_at(&self, world_x: f32, world_y: f32) -> Option<CellCoord> {
+        let inverse = self.transform.matrix().invert().unwrap_or_else(Matrix4::identity);
+        let local = inverse * Vector4::new(world_x, world_y, 0.0, 1.0);
+        let cell = CellCoord::new(
+            (local.x / self.tile_width).floor() as i32,
+            (local.y / self.tile_height).floor() as i32,
+        );
+        self.in_bounds(cell).then_some(cell)
+    }
+
+    // Reads the window's grid, so it only lines up for maps laid over that grid with `for_grid`.
+    pub fn cell_under_cursor(&self, window: &Window) -> Option<CellCoord> {
+        window.cursor_cell.filter(|&cell| self.in_bounds(cell))
+    }
+
+    pub fn draw(&mut self, renderer: &mut Renderer2D) {
+        if self.built_transform != Some(self.transform) {
+            for layer in &mut self.layers {
+                layer.chunks.iter_mut().for_each(|chunk| chunk.dirty = true);
+            }
+            self.built_transform = Some(self.transform);
+        }
+
+        let material = Material::Texture(self.sheet.texture().id());
+        let model = self.transform.affine_rows();
+        let chunks_x = self.chunks_x();
+        for layer_index in 0..self.layers.len() {
+            if !self.layers[layer_index].visible {
+                continue;
+            }
+            for chunk_index in 0..self.layers[layer_index].chunks.len() {
+                if self.layers[layer_index].chunks[chunk_index].dirty {
+                    let chunk_col = chunk_index as u32 % chunks_x;
+                    let chunk_row = chunk_index as u32 / chunks_x;
+                    let (vertices, indices) = self.build_chunk(layer_index, chunk_col, chunk_row, model);
+                    let chunk = &mut self.layers[layer_index].chunks[chunk_index];
+                    chunk
+                        .mesh
+                        .get_or_insert_with(|| StaticMesh::new(Primitive::Triangles, material))
+                        .upload(&vertices, &indices);
+                    chunk.dirty = false;
+                }
+
+                if let Some(mesh) = self.layers[layer_index].chunks[chunk_index].mesh.as_mut() {
+                    mesh.set_material(material);
+                    renderer.draw_mesh(mesh);
+                }
+            }
+        }
+    }
+
+    fn build_chunk(&self, layer: usize, chunk_col: u32, chunk_row: u32, model: [[f32; 3]; 2]) -> (Vec<Vertex2D>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let texture = self.sheet.texture();
+        let first_col = chunk_col * self.chunk_size;
+        let first_row = chunk_row * self.chunk_size;
+
+        for row in first_row..(first_row + self.chunk_size).min(self.rows) {
+            for col in first_col..(first_col + self.chunk_size).min(self.cols) {
+                let Some(tile) = self.layers[layer].tiles[(row * self.cols + col) as usize] else {
+                    continue;
+                };
+                let Some(region) = self.sheet.frame(tile.index) else {
+                    continue;
+                };
+
+                let left = col as f32 * self.tile_width;
+                let top = row as f32 * self.tile_height;
+                let mut corners = textured_quad_corners(
+                    [left, top, left + self.tile_width, top + self.tile_height],
+                    region.uv(texture),
+                    true,
+                );
+                orient_tile(&mut corners, tile.flags);
+
+                let base = vertices.len() as u32;
+                vertices.extend(corners.iter().map(|corner| Vertex2D { model, ..*corner }));
+                indices.extend(QUAD_INDICES.iter().map(|index| base + index));
+            }
+        }
+        (vertices, indices)
+    }
+}
+
+// Corners arrive as [top-right, bottom-right, bottom-left, top-left]. Flips are undone in
+// reverse order (vertical, horizontal, then diagonal) to find which texel each corner shows.
+fn orient_tile(corners: &mut [Vertex2D; 4], flags: TileFlags) {
+    if flags == TileFlags::NONE {
+        return;
+    }
+    let positions = [(1, 0), (1, 1), (0, 1), (0, 0)];
+    let original: Vec<[f32; 2]> = corners.iter().map(|corner| corner.tex_coords).collect();
+    let tex_coords_at = |x: i32, y: i32| {
+        let slot = positions.iter().position(|&position| position == (x, y)).unwrap_or(0);
+        original[slot]
+    };
+
+    for (corner, &(x, y)) in corners.iter_mut().zip(positions.iter()) {
+        let (mut tx, mut ty) = (x, y);
+        if flags.contains(TileFlags::FLIP_HORIZONTAL) {
+            tx = 1 - tx;
+        }
+        if flags.contains(TileFlags::FLIP_VERTICAL) {
+            ty = 1 - ty;
+        }
+        if flags.contains(TileFlags::FLIP_DIAGONAL) {
+            std::mem::swap(&mut tx, &mut ty);
+        }
+        corner.tex_coords = tex_coords_at(tx, ty);
+    }
+}
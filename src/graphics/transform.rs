@@ -0,0 +1,87 @@
+use cgmath::{Matrix4, Rad, Vector2, Vector3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    pub translation: Vector2<f32>,
+    pub rotation: f32,
+    pub scale: Vector2<f32>,
+    pub origin: Vector2<f32>,
+}
+
+impl Transform2D {
+    pub fn new(x: f32, y: f32) -> Self {
+        Transform2D {
+            translation: Vector2::new(x, y),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale_x: f32, scale_y: f32) -> Self {
+        self.scale = Vector2::new(scale_x, scale_y);
+        self
+    }
+
+    pub fn with_origin(mut self, origin_x: f32, origin_y: f32) -> Self {
+        self.origin = Vector2::new(origin_x, origin_y);
+        self
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.translation = Vector2::new(x, y);
+    }
+
+    pub fn translate(&mut self, dx: f32, dy: f32) {
+        self.translation += Vector2::new(dx, dy);
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.rotation += angle;
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        (self.rotation % std::f32::consts::FRAC_PI_2).abs() < f32::EPSILON
+    }
+
+    // Rows of the 2x3 affine model matrix: translation * rotation * scale * -origin.
+    pub fn affine_rows(&self) -> [[f32; 3]; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let a = cos * self.scale.x;
+        let b = sin * self.scale.x;
+        let c = -sin * self.scale.y;
+        let d = cos * self.scale.y;
+        let tx = self.translation.x - (a * self.origin.x + c * self.origin.y);
+        let ty = self.translation.y - (b * self.origin.x + d * self.origin.y);
+        [[a, c, tx], [b, d, ty]]
+    }
+
+    pub fn apply(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let [row0, row1] = self.affine_rows();
+        Vector2::new(
+            row0[0] * point.x + row0[1] * point.y + row0[2],
+            row1[0] * point.x + row1[1] * point.y + row1[2],
+        )
+    }
+
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(Vector3::new(self.translation.x, self.translation.y, 0.0))
+            * Matrix4::from_angle_z(Rad(self.rotation))
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, 1.0)
+            * Matrix4::from_translation(Vector3::new(-self.origin.x, -self.origin.y, 0.0))
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D {
+            translation: Vector2::new(0.0, 0.0),
+            rotation: 0.0,
+            scale: Vector2::new(1.0, 1.0),
+            origin: Vector2::new(0.0, 0.0),
+        }
+    }
+}
@@ -1,58 +1,64 @@
-use std::{borrow::Cow, time::{Duration, Instant}};
+use std::time::{Duration, Instant};
 
+use cgmath::Vector2;
 use glfw::{Action, Context, GlfwReceiver, Key, PWindow, WindowEvent};
 
-use crate::{graphics::geometry::square::KSquare, logger::{self, LogLevel, Logger}};
+use crate::{error::{Error, Result}, grid::{CellCoord, Grid}};
 
-use super::geometry::line::KLine;
+use super::{camera::{Camera2D, CoordinateSystem}, geometry::{line::KLine, polyline::LineCap}, renderer::Renderer2D};
+
+type EventCallback = Box<dyn FnMut(&glfw::WindowEvent) + Send>;
 
 pub struct Window {
     glfw: glfw::Glfw,
     window_handler: PWindow,
     events: GlfwReceiver<(f64, WindowEvent)>,
-    event_callbacks: Vec<Box<dyn FnMut(&glfw::WindowEvent) + Send>>,
+    event_callbacks: Vec<EventCallback>,
     width: u32,
     height: u32,
-    pub cols: u32,
-    pub rows: u32,
+    pub camera: Camera2D,
     fps_limit: Option<u32>,
     last_frame_time: Instant,
-    grid_lines: Option<Vec<KLine>>,
-    pub cursor_pos_cell_x: f32,
-    pub cursor_pos_cell_y: f32,
+    grid: Option<Grid>,
+    grid_fit: Option<(u32, u32)>,
+    grid_color: [f32; 4],
+    grid_line_width: Option<f32>,
+    pub cursor_cell: Option<CellCoord>,
     pub cursor_pos_x: f32,
     pub cursor_pos_y: f32,
 }
 
 impl Window {
-    pub fn new(width: u32, height: u32, title: &str) -> Window {
+    pub fn new(width: u32, height: u32, title: &str) -> Result<Window> {
         use glfw::fail_on_errors;
-        let mut glfw = glfw::init(fail_on_errors!()).unwrap();
+        let mut glfw = glfw::init(fail_on_errors!())?;
 
         let (mut window, events) = glfw
             .create_window(width, height, title, glfw::WindowMode::Windowed)
-            .expect("Failed to create GLFW windowed");
+            .ok_or(Error::WindowCreation)?;
 
         window.set_framebuffer_size_polling(true);
         window.set_key_polling(true);
+        let (framebuffer_width, framebuffer_height) = window.get_framebuffer_size();
 
-        Window {
+        Ok(Window {
             glfw,
             window_handler: window,
             events,
             event_callbacks: Vec::new(),
-            width,
-            height,
-            cols: 10,
-            rows: 10,
+            width: framebuffer_width as u32,
+            height: framebuffer_height as u32,
+            camera: Camera2D::new(framebuffer_width as u32, framebuffer_height as u32),
             fps_limit: Some(120),
             last_frame_time: Instant::now(),
-            grid_lines: None,
+            grid: None,
+            grid_fit: None,
+            grid_color: [1.0, 1.0, 1.0, 1.0],
+            grid_line_width: None,
+            cursor_cell: None,
             cursor_pos_x: 900.0,
             cursor_pos_y: 900.0,
-            cursor_pos_cell_x: 900.0,
-            cursor_pos_cell_y: 900.0
-        }
+        })
     }
 
     pub fn init_gl(&mut self) {
@@ -62,6 +68,51 @@ impl Window {
             gl::Enable(gl::BLEND);
             gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
         }
+        self.apply_viewport();
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn set_coordinate_system(&mut self, coordinate_system: CoordinateSystem) {
+        self.camera.set_coordinate_system(coordinate_system);
+        self.apply_viewport();
+        self.fit_grid();
+    }
+
+    fn resize(&mut self, width: i32, height: i32) {
+        self.width = width.max(0) as u32;
+        self.height = height.max(0) as u32;
+        self.camera.set_viewport(self.width, self.height);
+        self.apply_viewport();
+        self.fit_grid();
+    }
+
+    fn cursor_to_framebuffer(&self, x: f64, y: f64) -> (f64, f64) {
+        let (window_width, window_height) = self.window_handler.get_size();
+        if window_width <= 0 || window_height <= 0 {
+            return (x, y);
+        }
+        (
+            x * self.width as f64 / window_width as f64,
+            y * self.height as f64 / window_height as f64,
+        )
+    }
+
+    fn apply_viewport(&self) {
+        if !gl::Viewport::is_loaded() {
+            return;
+        }
+        let viewport = self.camera.viewport();
+        unsafe {
+            gl::Viewport(
+                viewport.x.round() as i32,
+                viewport.y.round() as i32,
+                viewport.width.round() as i32,
+                viewport.height.round() as i32,
+            );
+        }
     }
 
     pub fn should_close(&self) -> bool {
@@ -83,94 +134,77 @@ impl Window {
         self.enforce_fps_limit();
     }
 
-    pub fn set_grid(&mut self, rows: u32, cols: u32) {
-        self.cols = cols + 1;
-        self.rows = rows + 1;
-        self.update_grid_lines();
+    // Replaces the grid with `cols` x `rows` cells spanning the camera's whole world area,
+    // re-fitted whenever the viewport or coordinate system changes.
+    pub fn set_grid_size(&mut self, cols: u32, rows: u32) {
+        self.grid_fit = Some((cols, rows));
+        self.fit_grid();
     }
 
-    pub fn update_grid_lines(&mut self) {
-        let mut lines = Vec::new();
-        let color = [1.0, 1.0, 1.0, 1.0];
-        let row_step = 2.0 / (self.rows as f32 - 1.0);
-        let col_step = 2.0 / (self.cols as f32 - 1.0);
-
+    pub fn set_custom_grid(&mut self, grid: Grid) {
+        self.grid_fit = None;
+        self.grid = Some(grid);
+        self.update_cursor_cell();
+    }
 
-        for i in 0..self.rows {
-            let y = 1.0 - i as f32 * row_step;
-            lines.push(KLine::new(-1.0, y, 1.0, y, color));
-        }
+    pub fn clear_grid(&mut self) {
+        self.grid_fit = None;
+        self.grid = None;
+        self.cursor_cell = None;
+    }
 
-        for j in 0..self.cols {
-            let x = -1.0 + j as f32 * col_step;
-            lines.push(KLine::new(x, -1.0, x, 1.0, color));
-        }
+    pub fn grid(&self) -> Option<&Grid> {
+        self.grid.as_ref()
+    }
 
-        self.grid_lines = Some(lines);
+    fn fit_grid(&mut self) {
+        let Some((cols, rows)) = self.grid_fit else {
+            return;
+        };
+        let (width, height) = self.camera.world_size();
+        let grid = if self.camera.coordinate_system().is_y_down() {
+            Grid::fitted(cols, rows, Vector2::new(0.0, 0.0), Vector2::new(width, height))
+        } else {
+            Grid::fitted(cols, rows, Vector2::new(-width / 2.0, height / 2.0), Vector2::new(width / 2.0, -height / 2.0))
+        };
+        self.grid = Some(grid);
+        self.update_cursor_cell();
     }
 
     pub fn disable_cursor(&mut self) {
         self.window_handler.set_cursor_mode(glfw::CursorMode::Hidden);
     }
 
-    pub fn draw_grid(&self) {
-        if let Some(lines) = &self.grid_lines {
-            for line in lines {
-                line.draw();
-            }
-        }
+    pub fn set_grid_line_width(&mut self, width: Option<f32>) {
+        self.grid_line_width = width;
     }
 
-    pub fn convert_window_pos_to_grid_cell(&self, pos_x: u32, pos_y: u32) -> (f32, f32) {
-        let grid_size = self.get_grid_size();
-        let (grid_x, grid_y) = self.convert_to_grid_position(pos_x as u32, pos_y as u32);
-
-        let x_pos = grid_x + (grid_size.0 / 2.0);
-        let y_pos = grid_y + (grid_size.1 / 2.0);
-
-        let mut x_pos_rounded = (x_pos * (self.rows as f32 - 1.0) / 2.0).floor() / ((self.rows as f32 - 1.0) / 2.0);
-        let mut y_pos_rounded = (y_pos* (self.cols as f32 - 1.0) / 2.0).floor() / ((self.cols as f32 - 1.0) / 2.0);
+    pub fn set_grid_color(&mut self, color: [f32; 4]) {
+        self.grid_color = color;
+    }
 
-        if self.rows % 2 != 0 && self.cols % 2 != 0 {
-            let x_cell = (grid_x / grid_size.0).floor();
-            let y_cell = (grid_y / grid_size.1).floor();
-            
-            x_pos_rounded = (x_cell * grid_size.0) + (grid_size.0 / 2.0);
-            y_pos_rounded = (y_cell * grid_size.1) + (grid_size.1 / 2.0);
-            
-            println!("x_pos: {} - y_pos: {}", x_pos_rounded, y_pos_rounded);
+    pub fn draw_grid(&self, renderer: &mut Renderer2D) {
+        let Some(grid) = &self.grid else {
+            return;
+        };
+        for (start, end) in grid.lines() {
+            let line = KLine::new(start.x, start.y, end.x, end.y, self.grid_color);
+            match self.grid_line_width {
+                Some(width) => line.to_polyline(width).with_cap(LineCap::Square).draw(renderer),
+                None => line.draw(renderer),
+            }
         }
-        
-
-        (x_pos_rounded, y_pos_rounded)
     }
 
     pub fn set_resizable(&mut self, resizable: bool) {
         self.window_handler.set_resizable(resizable);
     }
 
-    pub fn convert_grid_pos_to_grid_cell(&self, norm_x: f32, norm_y: f32) -> (f32, f32) {
-        let grid_size = self.get_grid_size();
-    
-        let x_pos = norm_x * (grid_size.0 / 2.0);
-        let y_pos = norm_y * (grid_size.1 / 2.0);
-    
-        let x_pos_rounded = (x_pos * (self.rows as f32 - 1.0) / 2.0).floor() / ((self.rows as f32 - 1.0) / 2.0);
-        let y_pos_rounded = (y_pos * (self.cols as f32 - 1.0) / 2.0).floor() / ((self.cols as f32 - 1.0) / 2.0);
-    
-        (x_pos_rounded, y_pos_rounded)
-    }
-
-    pub fn convert_to_grid_position(&self, pos_x: u32, pos_y: u32) -> (f32, f32) {
-        let x_grid = 2.0 * (pos_x as f32) / (self.width as f32) - 1.0;
-        let y_grid = 1.0 - 2.0 * (pos_y as f32) / (self.height as f32);
-        (x_grid, y_grid)
-    }
-
-    pub fn get_grid_size(&self) -> (f32, f32) {
-        let grid_size_x = 2.0 / (self.cols as f32 - 1.0);
-        let grid_size_y = 2.0 / (self.rows as f32 - 1.0);
-        (grid_size_x, grid_size_y)
+    fn update_cursor_cell(&mut self) {
+        self.cursor_cell = self
+            .grid
+            .as_ref()
+            .and_then(|grid| grid.cell_at(self.cursor_pos_x, self.cursor_pos_y));
     }
 
     pub fn is_key_down(&self, key: glfw::Key) -> bool {
@@ -178,7 +212,8 @@ impl Window {
     }
 
     pub fn process_events_no_cb(&mut self) {
-        for (_, event) in glfw::flush_messages(&self.events) {
+        let events: Vec<_> = glfw::flush_messages(&self.events).collect();
+        for (_, event) in events {
             
             for callback in &mut self.event_callbacks {
                 callback(&event);
@@ -186,16 +221,14 @@ impl Window {
 
             match event {
                 glfw::WindowEvent::FramebufferSize(width, height) => {
-                    unsafe {gl::Viewport(0,0, width, height)}
+                    self.resize(width, height);
                 }
                 glfw::WindowEvent::CursorPos(x, y) => {
-                    let (cell_x, cell_y) = self.convert_window_pos_to_grid_cell(x as u32, y as u32);
-                    let (grid_x, grid_y) = self.convert_to_grid_position(x as u32, y as u32);
-                    
-                    self.cursor_pos_cell_x = cell_x;
-                    self.cursor_pos_cell_y = cell_y;
-                    self.cursor_pos_x = grid_x;
-                    self.cursor_pos_y = grid_y;
+                    let (x, y) = self.cursor_to_framebuffer(x, y);
+                    let world = self.camera.screen_to_world(x as f32, y as f32);
+                    self.cursor_pos_x = world.x;
+                    self.cursor_pos_y = world.y;
+                    self.update_cursor_cell();
                 }
                 _ => {}
             }
@@ -222,12 +255,13 @@ impl Window {
     where
         F: FnMut(&glfw::WindowEvent),
     {
-        for (_, event) in glfw::flush_messages(&self.events) {
+        let events: Vec<_> = glfw::flush_messages(&self.events).collect();
+        for (_, event) in events {
             callback(&event);
 
             match event {
                 glfw::WindowEvent::FramebufferSize(width, height) => {
-                    unsafe { gl::Viewport(0, 0, width, height) }
+                    self.resize(width, height);
                 }
                 glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                     self.window_handler.set_should_close(true);
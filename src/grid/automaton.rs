@@ -0,0 +1,254 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+use super::{map::GridMap, CellCoord, Connectivity};
+
+// How neighbours beyond the map edge are read: from the opposite edge, from the nearest
+// edge cell, or not at all (edge cells simply have fewer neighbours).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMode {
+    Wrap,
+    Clamp,
+    #[default]
+    Ignore,
+}
+
+// Outer-totalistic rule for two-state automata: a dead cell is born with a neighbour count in
+// `birth`, a live one survives with a count in `survival`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifeRule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+impl LifeRule {
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mut rule = Self { birth: [false; 9], survival: [false; 9] };
+        birth.iter().filter(|&&count| count <= 8).for_each(|&count| rule.birth[count as usize] = true);
+        survival.iter().filter(|&&count| count <= 8).for_each(|&count| rule.survival[count as usize] = true);
+        rule
+    }
+
+    pub fn game_of_life() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    // B5678/S45678, the usual smoothing rule for cave generation from random noise.
+    pub fn caves() -> Self {
+        Self::new(&[5, 6, 7, 8], &[4, 5, 6, 7, 8])
+    }
+
+    pub fn next_state(&self, alive: bool, live_neighbors: usize) -> bool {
+        let counts = if alive { &self.survival } else { &self.birth };
+        counts.get(live_neighbors).copied().unwrap_or(false)
+    }
+}
+
+// Accepts "B3/S23" in either order and any case, and the bare "23/3" survival/birth notation.
+impl FromStr for LifeRule {
+    type Err = Error;
+
+    fn from_str(rule: &str) -> Result<Self> {
+        let invalid = |message: &str| Error::AutomatonRule { rule: rule.to_string(), message: message.to_string() };
+        let digits = |part: &str| -> Result<Vec<u8>> {
+            part.chars()
+                .map(|digit| match digit.to_digit(10) {
+                    Some(count) if count <= 8 => Ok(count as u8),
+                    _ => Err(invalid(&format!("'{}' is not a neighbour count between 0 and 8", digit))),
+                })
+                .collect()
+        };
+
+        let (first, second) = rule.trim().split_once('/').ok_or_else(|| invalid("expected two parts separated by '/'"))?;
+        let mut birth = None;
+        let mut survival = None;
+        for part in [first, second] {
+            let mut chars = part.chars();
+            match chars.next().map(|prefix| prefix.to_ascii_uppercase()) {
+                Some('B') => birth = Some(digits(chars.as_str())?),
+                Some('S') => survival = Some(digits(chars.as_str())?),
+                _ if birth.is_none() && survival.is_none() => {
+                    survival = Some(digits(first)?);
+                    birth = Some(digits(second)?);
+                    break;
+                }
+                _ => return Err(invalid("mixes prefixed and bare parts")),
+            }
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self::new(&birth, &survival)),
+            _ => Err(invalid("needs one 'B' and one 'S' part")),
+        }
+    }
+}
+
+// Double-buffered automaton over a `GridMap`: each step reads only the previous generation
+// and writes into a second buffer that is swapped in afterwards.
+pub struct Automaton<T> {
+    current: GridMap<T>,
+    next: GridMap<T>,
+    edges: EdgeMode,
+    neighborhood: Connectivity,
+    generation: u64,
+}
+
+impl<T: Clone> Automaton<T> {
+    pub fn new(initial: GridMap<T>, edges: EdgeMode) -> Self {
+        Self {
+            next: initial.clone(),
+            current: initial,
+            edges,
+            neighborhood: Connectivity::Eight,
+            generation: 0,
+        }
+    }
+
+    // `Eight` is the Moore neighbourhood used by Life-like rules, `Four` the von Neumann one.
+    pub fn with_neighborhood(mut self, neighborhood: Connectivity) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn state(&self) -> &GridMap<T> {
+        &self.current
+    }
+
+    // Edits apply to the current generation, e.g. to paint cells between steps. Only cell
+    // values are exposed so the map keeps the size of the second buffer.
+    pub fn get_mut(&mut self, cell: CellCoord) -> Option<&mut T> {
+        self.current.get_mut(cell)
+    }
+
+    pub fn set(&mut self, cell: CellCoord, value: T) -> Option<T> {
+        self.current.set(cell, value)
+    }
+
+    pub fn into_state(self) -> GridMap<T> {
+        self.current
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn resolve(&self, cell: CellCoord) -> Option<CellCoord> {
+        if self.current.in_bounds(cell) {
+            return Some(cell);
+        }
+        let (cols, rows) = (self.current.cols() as i32, self.current.rows() as i32);
+        match self.edges {
+            EdgeMode::Wrap => Some(CellCoord::new(cell.col.rem_euclid(cols), cell.row.rem_euclid(rows))),
+            EdgeMode::Clamp => Some(CellCoord::new(cell.col.clamp(0, cols - 1), cell.row.clamp(0, rows - 1))),
+            EdgeMode::Ignore => None,
+        }
+    }
+
+    // `rule` gets each cell, its current value and its neighbours' values, and returns the value
+    // for the next generation.
+    pub fn step_with(&mut self, mut rule: impl FnMut(CellCoord, &T, &[&T]) -> T) {
+        if self.current.cols() == 0 || self.current.rows() == 0 {
+            return;
+        }
+        let mut neighbors = Vec::with_capacity(8);
+        for (cell, value) in self.current.iter() {
+            neighbors.clear();
+            for &(delta_col, delta_row) in self.neighborhood.offsets() {
+                if let Some(neighbor) = self.resolve(cell.offset(delta_col, delta_row)) {
+                    neighbors.push(&self.current[neighbor]);
+                }
+            }
+            self.next[cell] = rule(cell, value, &neighbors);
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+    }
+}
+
+impl Automaton<bool> {
+    pub fn step(&mut self, rule: &LifeRule) {
+        self.step_with(|_, &alive, neighbors| {
+            rule.next_state(alive, neighbors.iter().filter(|&&&neighbor| neighbor).count())
+        });
+    }
+
+    pub fn step_n(&mut self, rule: &LifeRule, steps: usize) {
+        for _ in 0..steps {
+            self.step(rule);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::ascii_map;
+
+    fn pattern(rows: &[&str]) -> GridMap<bool> {
+        ascii_map(rows).map(|_, &symbol| symbol == b'#')
+    }
+
+    #[test]
+    fn parses_rule_strings() {
+        let life = LifeRule::game_of_life();
+        assert_eq!("B3/S23".parse::<LifeRule>().unwrap(), life);
+        assert_eq!("s23/b3".parse::<LifeRule>().unwrap(), life);
+        assert_eq!("23/3".parse::<LifeRule>().unwrap(), life);
+        assert_eq!("B5678/S45678".parse::<LifeRule>().unwrap(), LifeRule::caves());
+        assert_eq!("B/S".parse::<LifeRule>().unwrap(), LifeRule::new(&[], &[]));
+        assert!("B39/S23".parse::<LifeRule>().is_err());
+        assert!("B3S23".parse::<LifeRule>().is_err());
+        assert!("B3/B2".parse::<LifeRule>().is_err());
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let start = pattern(&[".....", ".....", ".###.", ".....", "....."]);
+        let mut automaton = Automaton::new(start.clone(), EdgeMode::Ignore);
+        automaton.step(&LifeRule::game_of_life());
+        assert_eq!(automaton.state(), &pattern(&[".....", "..#..", "..#..", "..#..", "....."]));
+        automaton.step(&LifeRule::game_of_life());
+        assert_eq!(automaton.state(), &start);
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn glider_wraps_around_a_torus() {
+        let start = pattern(&[".#....", "..#...", "###...", "......", "......", "......"]);
+        let mut automaton = Automaton::new(start.clone(), EdgeMode::Wrap);
+        // A glider moves one cell diagonally every 4 generations, so 24 bring it home on a 6x6 torus.
+        automaton.step_n(&LifeRule::game_of_life(), 24);
+        assert_eq!(automaton.state(), &start);
+    }
+
+    #[test]
+    fn edge_modes_change_neighbor_counts() {
+        let counts = |edges| {
+            let mut automaton = Automaton::new(GridMap::new(3, 3, 1u32), edges);
+            automaton.step_with(|_, _, neighbors| neighbors.len() as u32);
+            automaton.state()[CellCoord::new(0, 0)]
+        };
+        assert_eq!(counts(EdgeMode::Ignore), 3);
+        assert_eq!(counts(EdgeMode::Wrap), 8);
+        assert_eq!(counts(EdgeMode::Clamp), 8);
+    }
+
+    #[test]
+    fn cells_can_be_painted_between_steps() {
+        let mut automaton = Automaton::new(pattern(&["...", "...", "..."]), EdgeMode::Ignore);
+        for col in 0..3 {
+            automaton.set(CellCoord::new(col, 1), true);
+        }
+        assert_eq!(automaton.set(CellCoord::new(3, 1), true), None);
+        automaton.step(&LifeRule::game_of_life());
+        assert_eq!(automaton.state(), &pattern(&[".#.", ".#.", ".#."]));
+    }
+
+    #[test]
+    fn custom_rules_see_the_previous_generation() {
+        let mut automaton = Automaton::new(GridMap::from_fn(4, 1, |cell| cell.col), EdgeMode::Ignore)
+            .with_neighborhood(Connectivity::Four);
+        automaton.step_with(|_, &value, neighbors| neighbors.iter().map(|&&neighbor| neighbor).max().unwrap_or(value));
+        assert_eq!(automaton.state().values(), &[1, 2, 3, 2]);
+    }
+}
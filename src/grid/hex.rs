@@ -0,0 +1,322 @@
+use std::ops::{Add, Sub};
+
+use cgmath::Vector2;
+
+use crate::graphics::renderer::Renderer2D;
+
+use super::{draw_closed_outline, CellCoord};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+// Axial coordinates; the third cube coordinate is implied by q + r + s = 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    // Ordered counter-clockwise starting east (pointy-top) or south-east (flat-top), as on screen
+    // with rows growing downwards.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex { q: 1, r: 0 },
+        Hex { q: 1, r: -1 },
+        Hex { q: 0, r: -1 },
+        Hex { q: -1, r: 0 },
+        Hex { q: -1, r: 1 },
+        Hex { q: 0, r: 1 },
+    ];
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    // Returns `None` unless q + r + s = 0.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Self> {
+        (q + r + s == 0).then_some(Self { q, r })
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    // Rounds fractional axial coordinates to the hex containing them.
+    pub fn round(q: f32, r: f32) -> Self {
+        let s = -q - r;
+        let (mut rounded_q, mut rounded_r, rounded_s) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rounded_q - q).abs(), (rounded_r - r).abs(), (rounded_s - s).abs());
+        if dq > dr && dq > ds {
+            rounded_q = -rounded_r - rounded_s;
+        } else if dr > ds {
+            rounded_r = -rounded_q - rounded_s;
+        }
+        Self::new(rounded_q as i32, rounded_r as i32)
+    }
+
+    pub fn scale(self, factor: i32) -> Self {
+        Self::new(self.q * factor, self.r * factor)
+    }
+
+    pub fn neighbor(self, direction: usize) -> Self {
+        self + Self::DIRECTIONS[direction % 6]
+    }
+
+    pub fn neighbors(self) -> [Hex; 6] {
+        Self::DIRECTIONS.map(|direction| self + direction)
+    }
+
+    pub fn distance(self, other: Hex) -> u32 {
+        let delta = self - other;
+        (delta.q.unsigned_abs() + delta.r.unsigned_abs() + delta.s().unsigned_abs()) / 2
+    }
+
+    // The 6 * radius hexes exactly `radius` steps away; just `self` for radius 0.
+    pub fn ring(self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut hexes = Vec::with_capacity(6 * radius as usize);
+        let mut current = self + Self::DIRECTIONS[4].scale(radius as i32);
+        for direction in 0..6 {
+            for _ in 0..radius {
+                hexes.push(current);
+                current = current.neighbor(direction);
+            }
+        }
+        hexes
+    }
+
+    // Every hex within `radius`, ring by ring outwards from `self`.
+    pub fn spiral(self, radius: u32) -> Vec<Hex> {
+        (0..=radius).flat_map(|ring| self.ring(ring)).collect()
+    }
+
+    // Hexes crossed by the straight line between the two centres, both ends included.
+    pub fn line_to(self, other: Hex) -> Vec<Hex> {
+        let steps = self.distance(other);
+        if steps == 0 {
+            return vec![self];
+        }
+        // Nudging off the exact edges keeps rounding consistent where the line runs along one.
+        let (start_q, start_r) = (self.q as f32 + 1e-6, self.r as f32 + 2e-6);
+        let (end_q, end_r) = (other.q as f32 + 1e-6, other.r as f32 + 2e-6);
+        (0..=steps)
+            .map(|step| {
+                let t = step as f32 / steps as f32;
+                Hex::round(start_q + (end_q - start_q) * t, start_r + (end_r - start_r) * t)
+            })
+            .collect()
+    }
+
+    // Offset coordinates for storing hexes in rectangular containers: odd rows are shifted
+    // right for pointy-top layouts, odd columns shifted down for flat-top ones.
+    pub fn to_offset(self, orientation: HexOrientation) -> CellCoord {
+        match orientation {
+            HexOrientation::PointyTop => CellCoord::new(self.q + (self.r - (self.r & 1)) / 2, self.r),
+            HexOrientation::FlatTop => CellCoord::new(self.q, self.r + (self.q - (self.q & 1)) / 2),
+        }
+    }
+
+    pub fn from_offset(cell: CellCoord, orientation: HexOrientation) -> Self {
+        match orientation {
+            HexOrientation::PointyTop => Hex::new(cell.col - (cell.row - (cell.row & 1)) / 2, cell.row),
+            HexOrientation::FlatTop => Hex::new(cell.col, cell.row - (cell.col - (cell.col & 1)) / 2),
+        }
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HexOrientation {
+    #[default]
+    PointyTop,
+    FlatTop,
+}
+
+// Places hexes in world space. `size` is the centre-to-corner radius along each axis, `origin`
+// the centre of hex (0, 0); r grows towards +y, or towards -y when `flip_y` is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexGrid {
+    pub orientation: HexOrientation,
+    pub size: Vector2<f32>,
+    pub origin: Vector2<f32>,
+    pub flip_y: bool,
+}
+
+impl HexGrid {
+    pub fn new(orientation: HexOrientation, size: f32) -> Self {
+        Self {
+            orientation,
+            size: Vector2::new(size, size),
+            origin: Vector2::new(0.0, 0.0),
+            flip_y: false,
+        }
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.size = Vector2::new(width, height);
+        self
+    }
+
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Vector2::new(x, y);
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    fn y_sign(&self) -> f32 {
+        if self.flip_y { -1.0 } else { 1.0 }
+    }
+
+    pub fn hex_to_world(&self, hex: Hex) -> Vector2<f32> {
+        let (q, r) = (hex.q as f32, hex.r as f32);
+        let (x, y) = match self.orientation {
+            HexOrientation::PointyTop => (SQRT_3 * q + SQRT_3 / 2.0 * r, 1.5 * r),
+            HexOrientation::FlatTop => (1.5 * q, SQRT_3 / 2.0 * q + SQRT_3 * r),
+        };
+        Vector2::new(self.origin.x + x * self.size.x, self.origin.y + self.y_sign() * y * self.size.y)
+    }
+
+    // Fractional axial coordinates of a world position, before rounding.
+    pub fn world_to_axial(&self, x: f32, y: f32) -> (f32, f32) {
+        let local_x = (x - self.origin.x) / self.size.x;
+        let local_y = self.y_sign() * (y - self.origin.y) / self.size.y;
+        match self.orientation {
+            HexOrientation::PointyTop => (SQRT_3 / 3.0 * local_x - local_y / 3.0, 2.0 / 3.0 * local_y),
+            HexOrientation::FlatTop => (2.0 / 3.0 * local_x, -local_x / 3.0 + SQRT_3 / 3.0 * local_y),
+        }
+    }
+
+    pub fn world_to_hex(&self, x: f32, y: f32) -> Hex {
+        let (q, r) = self.world_to_axial(x, y);
+        Hex::round(q, r)
+    }
+
+    pub fn corners(&self, hex: Hex) -> [Vector2<f32>; 6] {
+        let center = self.hex_to_world(hex);
+        let start_angle = match self.orientation {
+            HexOrientation::PointyTop => 30.0f32,
+            HexOrientation::FlatTop => 0.0,
+        };
+        std::array::from_fn(|corner| {
+            let angle = (start_angle + 60.0 * corner as f32).to_radians();
+            Vector2::new(
+                center.x + self.size.x * angle.cos(),
+                center.y + self.y_sign() * self.size.y * angle.sin(),
+            )
+        })
+    }
+
+    // `cols` x `rows` hexes laid out as a rectangle in offset coordinates.
+    pub fn rectangle(&self, cols: u32, rows: u32) -> Vec<Hex> {
+        CellCoord::row_major(cols, rows)
+            .map(|cell| Hex::from_offset(cell, self.orientation))
+            .collect()
+    }
+
+    pub fn draw_outline(&self, renderer: &mut Renderer2D, hex: Hex, color: [f32; 4], width: Option<f32>) {
+        draw_closed_outline(renderer, &self.corners(hex), color, width);
+    }
+
+    pub fn draw_outlines(&self, renderer: &mut Renderer2D, hexes: &[Hex], color: [f32; 4], width: Option<f32>) {
+        for &hex in hexes {
+            self.draw_outline(renderer, hex, color, width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_round_trip_for_both_orientations() {
+        for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop] {
+            for flip_y in [false, true] {
+                let grid = HexGrid::new(orientation, 24.0).with_origin(13.0, -7.0).with_flip_y(flip_y);
+                for hex in Hex::default().spiral(4) {
+                    let center = grid.hex_to_world(hex);
+                    assert_eq!(grid.world_to_hex(center.x, center.y), hex);
+                    for corner in grid.corners(hex) {
+                        let inside = center + (corner - center) * 0.95;
+                        assert_eq!(grid.world_to_hex(inside.x, inside.y), hex);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube_coordinates_sum_to_zero() {
+        let hex = Hex::new(3, -5);
+        assert_eq!(hex.cube(), (3, -5, 2));
+        assert_eq!(Hex::from_cube(3, -5, 2), Some(hex));
+        assert_eq!(Hex::from_cube(3, -5, 1), None);
+    }
+
+    #[test]
+    fn neighbors_are_one_step_away() {
+        let hex = Hex::new(2, -1);
+        for neighbor in hex.neighbors() {
+            assert_eq!(hex.distance(neighbor), 1);
+        }
+        assert_eq!(Hex::new(0, 0).distance(Hex::new(3, -1)), 3);
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        let center = Hex::new(1, 1);
+        for radius in 0..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), if radius == 0 { 1 } else { 6 * radius as usize });
+            assert!(ring.iter().all(|&hex| center.distance(hex) == radius));
+        }
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 1 + 6 + 12 + 18);
+        assert_eq!(spiral[0], center);
+    }
+
+    #[test]
+    fn lines_are_contiguous() {
+        let start = Hex::new(-2, 0);
+        let end = Hex::new(3, -4);
+        let line = start.line_to(end);
+        assert_eq!(line.len(), start.distance(end) as usize + 1);
+        assert_eq!((line[0], line[line.len() - 1]), (start, end));
+        assert!(line.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop] {
+            for hex in Hex::new(-1, 2).spiral(3) {
+                assert_eq!(Hex::from_offset(hex.to_offset(orientation), orientation), hex);
+            }
+        }
+        let grid = HexGrid::new(HexOrientation::FlatTop, 1.0);
+        assert_eq!(grid.rectangle(4, 3).len(), 12);
+    }
+}
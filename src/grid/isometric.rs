@@ -0,0 +1,274 @@
+use cgmath::Vector2;
+
+use crate::graphics::renderer::Renderer2D;
+
+use super::{draw_closed_outline, CellCoord};
+
+// `Diamond` rotates the whole map 45 degrees so it forms one large diamond. `Staggered` keeps
+// rows horizontal, each row half a tile lower than the previous and odd rows shifted half a
+// tile right, so the map fills a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsoLayout {
+    #[default]
+    Diamond,
+    Staggered,
+}
+
+// `origin` is the top-left corner of the whole map's bounding box; world y grows downwards,
+// or upwards when `flip_y` is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsoGrid {
+    pub layout: IsoLayout,
+    pub cols: u32,
+    pub rows: u32,
+    pub tile_width: f32,
+    pub tile_height: f32,
+    pub origin: Vector2<f32>,
+    pub flip_y: bool,
+}
+
+impl IsoGrid {
+    pub fn new(layout: IsoLayout, cols: u32, rows: u32, tile_width: f32, tile_height: f32) -> Self {
+        Self {
+            layout,
+            cols,
+            rows,
+            tile_width,
+            tile_height,
+            origin: Vector2::new(0.0, 0.0),
+            flip_y: false,
+        }
+    }
+
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Vector2::new(x, y);
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    fn y_sign(&self) -> f32 {
+        if self.flip_y { -1.0 } else { 1.0 }
+    }
+
+    fn local_to_world(&self, x: f32, y: f32) -> Vector2<f32> {
+        Vector2::new(self.origin.x + x, self.origin.y + self.y_sign() * y)
+    }
+
+    pub fn in_bounds(&self, cell: CellCoord) -> bool {
+        cell.is_within(self.cols, self.rows)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = CellCoord> {
+        CellCoord::row_major(self.cols, self.rows)
+    }
+
+    // Both layouts share one lattice of tile centres, so staggered cells are addressed through
+    // the diamond cell with the same centre.
+    fn diamond_of(&self, cell: CellCoord) -> CellCoord {
+        match self.layout {
+            IsoLayout::Diamond => cell,
+            IsoLayout::Staggered => {
+                let across = 2 * cell.col + (cell.row & 1);
+                CellCoord::new((cell.row + across) / 2, (cell.row - across) / 2)
+            }
+        }
+    }
+
+    fn cell_of_diamond(&self, diamond: CellCoord) -> CellCoord {
+        match self.layout {
+            IsoLayout::Diamond => diamond,
+            IsoLayout::Staggered => {
+                let row = diamond.col + diamond.row;
+                CellCoord::new((diamond.col - diamond.row - (row & 1)).div_euclid(2), row)
+            }
+        }
+    }
+
+    // Offset from the grid origin to the top corner of diamond cell (0, 0).
+    fn diamond_origin(&self) -> f32 {
+        match self.layout {
+            IsoLayout::Diamond => self.rows.saturating_sub(1) as f32 * self.tile_width / 2.0,
+            IsoLayout::Staggered => 0.0,
+        }
+    }
+
+    pub fn cell_to_world(&self, cell: CellCoord) -> Vector2<f32> {
+        let diamond = self.diamond_of(cell);
+        let x = self.diamond_origin() + (diamond.col - diamond.row + 1) as f32 * self.tile_width / 2.0;
+        let y = (diamond.col + diamond.row + 1) as f32 * self.tile_height / 2.0;
+        self.local_to_world(x, y)
+    }
+
+    // Corners in order top, right, bottom, left as seen with y growing downwards.
+    pub fn cell_corners(&self, cell: CellCoord) -> [Vector2<f32>; 4] {
+        let center = self.cell_to_world(cell);
+        let (half_width, half_height) = (self.tile_width / 2.0, self.y_sign() * self.tile_height / 2.0);
+        [
+            Vector2::new(center.x, center.y - half_height),
+            Vector2::new(center.x + half_width, center.y),
+            Vector2::new(center.x, center.y + half_height),
+            Vector2::new(center.x - half_width, center.y),
+        ]
+    }
+
+    // Unbounded picking. A point on a shared diamond edge belongs to the cell below it.
+    pub fn world_to_cell(&self, x: f32, y: f32) -> CellCoord {
+        let across = (x - self.origin.x - self.diamond_origin()) / (self.tile_width / 2.0) - 1.0;
+        let down = self.y_sign() * (y - self.origin.y) / (self.tile_height / 2.0);
+        let diamond = CellCoord::new(((down + across) / 2.0).floor() as i32, ((down - across) / 2.0).floor() as i32);
+        self.cell_of_diamond(diamond)
+    }
+
+    pub fn cell_at(&self, x: f32, y: f32) -> Option<CellCoord> {
+        let cell = self.world_to_cell(x, y);
+        self.in_bounds(cell).then_some(cell)
+    }
+
+    pub fn draw_cell_outline(&self, renderer: &mut Renderer2D, cell: CellCoord, color: [f32; 4], width: Option<f32>) {
+        draw_closed_outline(renderer, &self.cell_corners(cell), color, width);
+    }
+
+    pub fn draw_outlines(&self, renderer: &mut Renderer2D, color: [f32; 4], width: Option<f32>) {
+        for cell in self.cells() {
+            self.draw_cell_outline(renderer, cell, color, width);
+        }
+    }
+}
+
+// Position of a drawable on the map. `col`/`row` may be fractional for objects between cells;
+// `height` lifts it off the ground, so of two things on one cell the higher is drawn later.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthKey {
+    pub col: f32,
+    pub row: f32,
+    pub height: f32,
+}
+
+impl DepthKey {
+    pub fn new(col: f32, row: f32, height: f32) -> Self {
+        Self { col, row, height }
+    }
+
+    pub fn cell(cell: CellCoord, height: f32) -> Self {
+        Self::new(cell.col as f32, cell.row as f32, height)
+    }
+
+    // Distance from the back of the map: the sum of the diamond axes, or the row when staggered.
+    fn depth(&self, layout: IsoLayout) -> f32 {
+        match layout {
+            IsoLayout::Diamond => self.col + self.row,
+            IsoLayout::Staggered => self.row,
+        }
+    }
+}
+
+// Collects drawables and hands them back back-to-front. Ties keep insertion order, so tiles
+// pushed before the objects standing on them stay underneath.
+pub struct DepthSorter<T> {
+    layout: IsoLayout,
+    entries: Vec<(DepthKey, T)>,
+}
+
+impl<T> DepthSorter<T> {
+    pub fn new(layout: IsoLayout) -> Self {
+        Self { layout, entries: Vec::new() }
+    }
+
+    pub fn push(&mut self, key: DepthKey, item: T) {
+        self.entries.push((key, item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn sort(&mut self) {
+        let layout = self.layout;
+        self.entries.sort_by(|(a, _), (b, _)| {
+            a.depth(layout)
+                .total_cmp(&b.depth(layout))
+                .then_with(|| a.height.total_cmp(&b.height))
+                .then_with(|| a.col.total_cmp(&b.col))
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(_, item)| item)
+    }
+
+    // Sorts and empties the sorter, keeping its allocation for the next frame.
+    pub fn drain_sorted(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.sort();
+        self.entries.drain(..).map(|(_, item)| item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grids() -> [IsoGrid; 3] {
+        [
+            IsoGrid::new(IsoLayout::Diamond, 5, 4, 64.0, 32.0).with_origin(10.0, 20.0),
+            IsoGrid::new(IsoLayout::Staggered, 5, 4, 64.0, 32.0).with_origin(-30.0, 5.0),
+            IsoGrid::new(IsoLayout::Diamond, 3, 6, 2.0, 1.0).with_flip_y(true),
+        ]
+    }
+
+    #[test]
+    fn centers_and_inner_corners_pick_their_cell() {
+        for grid in grids() {
+            for cell in grid.cells() {
+                let center = grid.cell_to_world(cell);
+                assert_eq!(grid.cell_at(center.x, center.y), Some(cell));
+                for corner in grid.cell_corners(cell) {
+                    let inside = center + (corner - center) * 0.97;
+                    assert_eq!(grid.world_to_cell(inside.x, inside.y), cell);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn diamond_edges_split_between_neighbours() {
+        let grid = IsoGrid::new(IsoLayout::Diamond, 4, 4, 64.0, 32.0);
+        let center = grid.cell_to_world(CellCoord::new(1, 1));
+        // Just across the lower-right edge lies the next column, across the lower-left the next row.
+        assert_eq!(grid.world_to_cell(center.x + 17.0, center.y + 9.0), CellCoord::new(2, 1));
+        assert_eq!(grid.world_to_cell(center.x - 17.0, center.y + 9.0), CellCoord::new(1, 2));
+        assert_eq!(grid.world_to_cell(center.x, center.y - 17.0), CellCoord::new(0, 0));
+    }
+
+    #[test]
+    fn staggered_rows_are_shifted() {
+        let grid = IsoGrid::new(IsoLayout::Staggered, 4, 4, 64.0, 32.0);
+        assert_eq!(grid.cell_to_world(CellCoord::new(0, 0)), Vector2::new(32.0, 16.0));
+        assert_eq!(grid.cell_to_world(CellCoord::new(0, 1)), Vector2::new(64.0, 32.0));
+        assert_eq!(grid.cell_to_world(CellCoord::new(1, 2)), Vector2::new(96.0, 48.0));
+        assert_eq!(grid.cell_at(2.0, 2.0), None);
+    }
+
+    #[test]
+    fn sorter_orders_back_to_front() {
+        let mut sorter = DepthSorter::new(IsoLayout::Diamond);
+        sorter.push(DepthKey::cell(CellCoord::new(2, 2), 0.0), "front tile");
+        sorter.push(DepthKey::cell(CellCoord::new(1, 1), 1.0), "player");
+        sorter.push(DepthKey::cell(CellCoord::new(1, 1), 0.0), "tile under player");
+        sorter.push(DepthKey::cell(CellCoord::new(0, 0), 0.0), "back tile");
+        let order: Vec<_> = sorter.drain_sorted().collect();
+        assert_eq!(order, vec!["back tile", "tile under player", "player", "front tile"]);
+        assert!(sorter.is_empty());
+    }
+}
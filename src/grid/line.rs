@@ -0,0 +1,112 @@
+use super::CellCoord;
+
+// Cells of the classic Bresenham line, one per step along the major axis, both ends included.
+pub fn bresenham(from: CellCoord, to: CellCoord) -> Vec<CellCoord> {
+    let (dx, dy) = ((to.col - from.col).abs(), -(to.row - from.row).abs());
+    let (step_x, step_y) = ((to.col - from.col).signum(), (to.row - from.row).signum());
+    let mut error = dx + dy;
+    let mut cell = from;
+    let mut cells = Vec::with_capacity(dx.max(-dy) as usize + 1);
+
+    loop {
+        cells.push(cell);
+        if cell == to {
+            return cells;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            cell.col += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            cell.row += step_y;
+        }
+    }
+}
+
+// Every cell the segment between the two cell centres touches, both ends included. Where the
+// segment passes exactly through a corner, the two cells sharing it come before the diagonal one.
+pub fn supercover(from: CellCoord, to: CellCoord) -> Vec<CellCoord> {
+    let mut cells = vec![from];
+    walk_supercover(from, to, |cell, corner| {
+        if let Some(sides) = corner {
+            cells.extend(sides);
+        }
+        cells.push(cell);
+        true
+    });
+    cells
+}
+
+// Visits the cells after `from` along the supercover line, with the two side cells when the step
+// crosses an exact corner. Stops early, returning false, once `visit` does.
+pub(super) fn walk_supercover(
+    from: CellCoord,
+    to: CellCoord,
+    mut visit: impl FnMut(CellCoord, Option<[CellCoord; 2]>) -> bool,
+) -> bool {
+    let (dx, dy) = (to.col - from.col, to.row - from.row);
+    let (step_x, step_y) = (dx.signum(), dy.signum());
+    let (nx, ny) = (dx.abs(), dy.abs());
+    let (mut ix, mut iy) = (0, 0);
+    let mut cell = from;
+
+    while ix < nx || iy < ny {
+        let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+        let corner = if decision == 0 {
+            let sides = [cell.offset(step_x, 0), cell.offset(0, step_y)];
+            cell = cell.offset(step_x, step_y);
+            ix += 1;
+            iy += 1;
+            Some(sides)
+        } else if decision < 0 {
+            cell = cell.offset(step_x, 0);
+            ix += 1;
+            None
+        } else {
+            cell = cell.offset(0, step_y);
+            iy += 1;
+            None
+        };
+        if !visit(cell, corner) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bresenham_has_one_cell_per_major_step() {
+        let line = bresenham(CellCoord::new(0, 0), CellCoord::new(5, -2));
+        assert_eq!(line.len(), 6);
+        assert_eq!(line.last(), Some(&CellCoord::new(5, -2)));
+        assert!(line.windows(2).all(|pair| pair[0].chebyshev_distance(pair[1]) == 1));
+        assert_eq!(bresenham(CellCoord::new(3, 3), CellCoord::new(3, 3)), vec![CellCoord::new(3, 3)]);
+    }
+
+    #[test]
+    fn supercover_touches_every_crossed_cell() {
+        let line = supercover(CellCoord::new(0, 0), CellCoord::new(4, 1));
+        assert!(line.windows(2).all(|pair| pair[0].manhattan_distance(pair[1]) == 1));
+        assert_eq!(line.len(), 6);
+
+        let diagonal = supercover(CellCoord::new(0, 0), CellCoord::new(2, 2));
+        assert_eq!(diagonal.len(), 7);
+        assert!(diagonal.contains(&CellCoord::new(1, 0)) && diagonal.contains(&CellCoord::new(0, 1)));
+    }
+
+    #[test]
+    fn supercover_is_symmetric() {
+        let (a, b) = (CellCoord::new(-2, 1), CellCoord::new(4, -3));
+        let mut forward = supercover(a, b);
+        let mut backward = supercover(b, a);
+        forward.sort();
+        backward.sort();
+        assert_eq!(forward, backward);
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::{Index, IndexMut};
+
+use crate::graphics::renderer::{Material, Primitive, Renderer2D, Vertex2D, QUAD_INDICES};
+
+use super::{CellCoord, Grid};
+
+// Row-major storage of one value per cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridMap<T> {
+    cols: u32,
+    rows: u32,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> GridMap<T> {
+    pub fn new(cols: u32, rows: u32, value: T) -> Self {
+        Self { cols, rows, cells: vec![value; cols as usize * rows as usize] }
+    }
+
+    pub fn for_grid(grid: &Grid, value: T) -> Self {
+        Self::new(grid.cols, grid.rows, value)
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.cells.iter_mut().for_each(|cell| *cell = value.clone());
+    }
+}
+
+impl<T> GridMap<T> {
+    pub fn from_fn(cols: u32, rows: u32, mut value_at: impl FnMut(CellCoord) -> T) -> Self {
+        let cells = CellCoord::row_major(cols, rows).map(&mut value_at).collect();
+        Self { cols, rows, cells }
+    }
+
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn in_bounds(&self, cell: CellCoord) -> bool {
+        cell.is_within(self.cols, self.rows)
+    }
+
+    fn index_of(&self, cell: CellCoord) -> Option<usize> {
+        cell.index_within(self.cols, self.rows)
+    }
+
+    pub fn get(&self, cell: CellCoord) -> Option<&T> {
+        self.index_of(cell).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, cell: CellCoord) -> Option<&mut T> {
+        self.index_of(cell).map(|index| &mut self.cells[index])
+    }
+
+    // Returns the previous value, or `None` (leaving the map untouched) when out of bounds.
+    pub fn set(&mut self, cell: CellCoord, value: T) -> Option<T> {
+        self.get_mut(cell).map(|slot| std::mem::replace(slot, value))
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (CellCoord, &T)> {
+        CellCoord::row_major(self.cols, self.rows).zip(&self.cells)
+    }
+
+    pub fn map<U>(&self, mut convert: impl FnMut(CellCoord, &T) -> U) -> GridMap<U> {
+        GridMap {
+            cols: self.cols,
+            rows: self.rows,
+            cells: self.iter().map(|(cell, value)| convert(cell, value)).collect(),
+        }
+    }
+}
+
+impl<T> Index<CellCoord> for GridMap<T> {
+    type Output = T;
+
+    fn index(&self, cell: CellCoord) -> &T {
+        match self.index_of(cell) {
+            Some(index) => &self.cells[index],
+            None => panic!("cell {:?} is outside a {}x{} grid map", cell, self.cols, self.rows),
+        }
+    }
+}
+
+impl<T> IndexMut<CellCoord> for GridMap<T> {
+    fn index_mut(&mut self, cell: CellCoord) -> &mut T {
+        match self.index_of(cell) {
+            Some(index) => &mut self.cells[index],
+            None => panic!("cell {:?} is outside a {}x{} grid map", cell, self.cols, self.rows),
+        }
+    }
+}
+
+impl GridMap<[f32; 4]> {
+    // Fills each cell of `grid` with its colour as one batch; fully transparent cells are skipped.
+    pub fn draw(&self, renderer: &mut Renderer2D, grid: &Grid) {
+        let mut vertices = Vec::with_capacity(self.cells.len() * 4);
+        let mut indices = Vec::with_capacity(self.cells.len() * 6);
+        for (cell, &color) in self.iter() {
+            if color[3] <= 0.0 || !grid.in_bounds(cell) {
+                continue;
+            }
+            let near = grid.cell_corner(cell);
+            let far = grid.cell_corner(cell.offset(1, 1));
+            let base = vertices.len() as u32;
+            vertices.extend([
+                Vertex2D::colored(far.x, near.y, color),
+                Vertex2D::colored(far.x, far.y, color),
+                Vertex2D::colored(near.x, far.y, color),
+                Vertex2D::colored(near.x, near.y, color),
+            ]);
+            indices.extend(QUAD_INDICES.iter().map(|index| base + index));
+        }
+        renderer.submit(Primitive::Triangles, Material::Color, &vertices, &indices);
+    }
+}
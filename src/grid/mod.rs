@@ -0,0 +1,332 @@
+use cgmath::Vector2;
+
+use crate::graphics::{
+    geometry::{line::KLine, polyline::KPolyline},
+    renderer::Renderer2D,
+};
+
+pub mod automaton;
+pub mod hex;
+pub mod isometric;
+pub mod line;
+pub mod map;
+pub mod pathfinding;
+pub mod visibility;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellCoord {
+    pub col: i32,
+    pub row: i32,
+}
+
+impl CellCoord {
+    pub fn new(col: i32, row: i32) -> Self {
+        Self { col, row }
+    }
+
+    pub fn offset(self, delta_col: i32, delta_row: i32) -> Self {
+        Self::new(self.col + delta_col, self.row + delta_row)
+    }
+
+    pub fn manhattan_distance(self, other: CellCoord) -> u32 {
+        self.col.abs_diff(other.col) + self.row.abs_diff(other.row)
+    }
+
+    pub fn chebyshev_distance(self, other: CellCoord) -> u32 {
+        self.col.abs_diff(other.col).max(self.row.abs_diff(other.row))
+    }
+
+    // Bounds and row-major layout shared by every `cols` x `rows` rectangle of cells.
+    pub fn is_within(self, cols: u32, rows: u32) -> bool {
+        self.col >= 0 && self.row >= 0 && (self.col as u32) < cols && (self.row as u32) < rows
+    }
+
+    pub fn index_within(self, cols: u32, rows: u32) -> Option<usize> {
+        self.is_within(cols, rows)
+            .then(|| self.row as usize * cols as usize + self.col as usize)
+    }
+
+    pub fn row_major(cols: u32, rows: u32) -> impl Iterator<Item = CellCoord> {
+        let cols = cols as i32;
+        (0..rows as i32).flat_map(move |row| (0..cols).map(move |col| CellCoord::new(col, row)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+// Orthogonal offsets come first, so `Four` is a prefix of `Eight`.
+const OFFSETS: [(i32, i32); 8] = [(1, 0), (0, 1), (-1, 0), (0, -1), (1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+impl Connectivity {
+    pub fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &OFFSETS[..4],
+            Connectivity::Eight => &OFFSETS,
+        }
+    }
+}
+
+// A rectangular grid of `cols` x `rows` cells. `origin` is the outer corner of cell (0, 0);
+// columns grow towards +x and rows towards +y, or towards -y when `flip_y` is set (y-up
+// worlds such as NDC, where row 0 should stay at the top).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    pub cols: u32,
+    pub rows: u32,
+    pub origin: Vector2<f32>,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub flip_y: bool,
+}
+
+impl Grid {
+    pub fn new(cols: u32, rows: u32, cell_width: f32, cell_height: f32) -> Self {
+        Self {
+            cols,
+            rows,
+            origin: Vector2::new(0.0, 0.0),
+            cell_width,
+            cell_height,
+            flip_y: false,
+        }
+    }
+
+    // Splits the rectangle between `top_left` and `bottom_right` into `cols` x `rows` cells.
+    pub fn fitted(cols: u32, rows: u32, top_left: Vector2<f32>, bottom_right: Vector2<f32>) -> Self {
+        let size = bottom_right - top_left;
+        Self {
+            cols,
+            rows,
+            origin: top_left,
+            cell_width: size.x.abs() / cols.max(1) as f32,
+            cell_height: size.y.abs() / rows.max(1) as f32,
+            flip_y: size.y < 0.0,
+        }
+    }
+
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Vector2::new(x, y);
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.cols as usize * self.rows as usize
+    }
+
+    pub fn cell_size(&self) -> (f32, f32) {
+        (self.cell_width, self.cell_height)
+    }
+
+    pub fn world_size(&self) -> (f32, f32) {
+        (self.cols as f32 * self.cell_width, self.rows as f32 * self.cell_height)
+    }
+
+    fn row_sign(&self) -> f32 {
+        if self.flip_y { -1.0 } else { 1.0 }
+    }
+
+    pub fn in_bounds(&self, cell: CellCoord) -> bool {
+        cell.is_within(self.cols, self.rows)
+    }
+
+    pub fn index(&self, cell: CellCoord) -> Option<usize> {
+        cell.index_within(self.cols, self.rows)
+    }
+
+    pub fn cell_from_index(&self, index: usize) -> Option<CellCoord> {
+        (index < self.cell_count()).then(|| {
+            CellCoord::new((index % self.cols as usize) as i32, (index / self.cols as usize) as i32)
+        })
+    }
+
+    // World position of the corner of `cell` nearest to the grid origin.
+    pub fn cell_corner(&self, cell: CellCoord) -> Vector2<f32> {
+        Vector2::new(
+            self.origin.x + cell.col as f32 * self.cell_width,
+            self.origin.y + self.row_sign() * cell.row as f32 * self.cell_height,
+        )
+    }
+
+    pub fn cell_to_world(&self, cell: CellCoord) -> Vector2<f32> {
+        Vector2::new(
+            self.origin.x + (cell.col as f32 + 0.5) * self.cell_width,
+            self.origin.y + self.row_sign() * (cell.row as f32 + 0.5) * self.cell_height,
+        )
+    }
+
+    pub fn cells_to_world(&self, cells: &[CellCoord]) -> Vec<Vector2<f32>> {
+        cells.iter().map(|&cell| self.cell_to_world(cell)).collect()
+    }
+
+    // Unbounded: positions outside the grid map to cells outside it, see `cell_at`.
+    pub fn world_to_cell(&self, x: f32, y: f32) -> CellCoord {
+        let local_x = (x - self.origin.x) / self.cell_width;
+        let local_y = self.row_sign() * (y - self.origin.y) / self.cell_height;
+        CellCoord::new(local_x.floor() as i32, local_y.floor() as i32)
+    }
+
+    pub fn cell_at(&self, x: f32, y: f32) -> Option<CellCoord> {
+        let cell = self.world_to_cell(x, y);
+        self.in_bounds(cell).then_some(cell)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = CellCoord> {
+        CellCoord::row_major(self.cols, self.rows)
+    }
+
+    pub fn neighbors(&self, cell: CellCoord, connectivity: Connectivity) -> impl Iterator<Item = CellCoord> + '_ {
+        connectivity
+            .offsets()
+            .iter()
+            .map(move |&(delta_col, delta_row)| cell.offset(delta_col, delta_row))
+            .filter(move |neighbor| self.in_bounds(*neighbor))
+    }
+
+    pub fn lines(&self) -> Vec<(Vector2<f32>, Vector2<f32>)> {
+        let mut lines = Vec::with_capacity((self.cols + self.rows + 2) as usize);
+        let cols = self.cols as i32;
+        let rows = self.rows as i32;
+        for row in 0..=rows {
+            lines.push((self.cell_corner(CellCoord::new(0, row)), self.cell_corner(CellCoord::new(cols, row))));
+        }
+        for col in 0..=cols {
+            lines.push((self.cell_corner(CellCoord::new(col, 0)), self.cell_corner(CellCoord::new(col, rows))));
+        }
+        lines
+    }
+}
+
+// Outline of a closed cell shape: thin lines when `width` is `None`, otherwise a closed
+// polyline of that width.
+fn draw_closed_outline(renderer: &mut Renderer2D, corners: &[Vector2<f32>], color: [f32; 4], width: Option<f32>) {
+    match width {
+        Some(width) => {
+            let points: Vec<(f32, f32)> = corners.iter().map(|corner| (corner.x, corner.y)).collect();
+            KPolyline::new(&points, width, color).closed(true).draw(renderer);
+        }
+        None => {
+            for (i, start) in corners.iter().enumerate() {
+                let end = corners[(i + 1) % corners.len()];
+                KLine::new(start.x, start.y, end.x, end.y, color).draw(renderer);
+            }
+        }
+    }
+}
+
+// Test maps drawn as text, one string per row; each cell holds its character.
+#[cfg(test)]
+fn ascii_map(rows: &[&str]) -> map::GridMap<u8> {
+    let cols = rows.first().map_or(0, |row| row.len());
+    assert!(rows.iter().all(|row| row.len() == cols), "ragged ascii map");
+    map::GridMap::from_fn(cols as u32, rows.len() as u32, |cell| {
+        rows[cell.row as usize].as_bytes()[cell.col as usize]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ndc_grid() -> Grid {
+        Grid::fitted(4, 3, Vector2::new(-1.0, 1.0), Vector2::new(1.0, -1.0))
+    }
+
+    #[test]
+    fn fitted_grid_flips_rows_for_y_up() {
+        let grid = ndc_grid();
+        assert!(grid.flip_y);
+        assert_eq!(grid.cell_size(), (0.5, 2.0 / 3.0));
+        assert_eq!(grid.cell_corner(CellCoord::new(0, 0)), Vector2::new(-1.0, 1.0));
+    }
+
+    #[test]
+    fn cell_world_round_trip() {
+        for grid in [ndc_grid(), Grid::new(7, 5, 32.0, 24.0).with_origin(-100.0, 40.0)] {
+            for cell in grid.cells() {
+                let center = grid.cell_to_world(cell);
+                assert_eq!(grid.world_to_cell(center.x, center.y), cell);
+                assert_eq!(grid.cell_at(center.x, center.y), Some(cell));
+            }
+        }
+    }
+
+    #[test]
+    fn corners_belong_to_the_following_cell() {
+        let grid = Grid::new(3, 3, 10.0, 10.0);
+        assert_eq!(grid.world_to_cell(10.0, 20.0), CellCoord::new(1, 2));
+        assert_eq!(grid.world_to_cell(9.999, 0.0), CellCoord::new(0, 0));
+        assert_eq!(grid.world_to_cell(-0.001, 0.0), CellCoord::new(-1, 0));
+    }
+
+    #[test]
+    fn row_zero_is_at_the_top_of_an_ndc_grid() {
+        let grid = ndc_grid();
+        assert_eq!(grid.cell_at(-0.9, 0.9), Some(CellCoord::new(0, 0)));
+        assert_eq!(grid.cell_at(0.9, -0.9), Some(CellCoord::new(3, 2)));
+    }
+
+    #[test]
+    fn bounds_checks() {
+        let grid = Grid::new(4, 2, 1.0, 1.0);
+        assert!(grid.in_bounds(CellCoord::new(3, 1)));
+        assert!(!grid.in_bounds(CellCoord::new(4, 1)));
+        assert!(!grid.in_bounds(CellCoord::new(0, 2)));
+        assert!(!grid.in_bounds(CellCoord::new(-1, 0)));
+        assert_eq!(grid.cell_at(4.5, 0.5), None);
+        assert_eq!(grid.cell_at(0.5, -0.5), None);
+    }
+
+    #[test]
+    fn rows_and_cols_are_not_swapped() {
+        let grid = Grid::new(5, 2, 1.0, 1.0);
+        assert_eq!(grid.cells().count(), 10);
+        assert_eq!(grid.cells().last(), Some(CellCoord::new(4, 1)));
+        assert_eq!(grid.index(CellCoord::new(4, 1)), Some(9));
+        assert_eq!(grid.cell_from_index(6), Some(CellCoord::new(1, 1)));
+        assert_eq!(grid.cell_from_index(10), None);
+    }
+
+    #[test]
+    fn row_major_layout_matches_indices() {
+        let cells: Vec<_> = CellCoord::row_major(3, 2).collect();
+        assert_eq!(cells.len(), 6);
+        for (index, cell) in cells.into_iter().enumerate() {
+            assert_eq!(cell.index_within(3, 2), Some(index));
+        }
+        assert_eq!(CellCoord::new(3, 0).index_within(3, 2), None);
+        assert!(!CellCoord::new(0, -1).is_within(3, 2));
+        assert_eq!(CellCoord::row_major(0, 5).count(), 0);
+    }
+
+    #[test]
+    fn neighbors_respect_connectivity_and_bounds() {
+        let grid = Grid::new(3, 3, 1.0, 1.0);
+        let center = CellCoord::new(1, 1);
+        assert_eq!(grid.neighbors(center, Connectivity::Four).count(), 4);
+        assert_eq!(grid.neighbors(center, Connectivity::Eight).count(), 8);
+
+        let corner = CellCoord::new(0, 0);
+        let mut four: Vec<_> = grid.neighbors(corner, Connectivity::Four).collect();
+        four.sort();
+        assert_eq!(four, vec![CellCoord::new(0, 1), CellCoord::new(1, 0)]);
+        assert_eq!(grid.neighbors(corner, Connectivity::Eight).count(), 3);
+    }
+
+    #[test]
+    fn lines_cover_every_boundary() {
+        let grid = Grid::new(4, 2, 1.0, 1.0);
+        let lines = grid.lines();
+        assert_eq!(lines.len(), 3 + 5);
+        assert_eq!(lines[0], (Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0)));
+        assert_eq!(lines[3], (Vector2::new(0.0, 0.0), Vector2::new(0.0, 2.0)));
+    }
+}
@@ -0,0 +1,416 @@
+use std::{cmp::Ordering, collections::{BinaryHeap, VecDeque}, f32::consts::SQRT_2};
+
+use super::{line::walk_supercover, map::GridMap, CellCoord, Connectivity, Grid};
+
+// Cost of entering a cell, or `None` when it is blocked. Step costs are this value times the
+// step length (1 orthogonally, sqrt(2) diagonally); A* stays optimal while every cost is >= 1.
+pub trait CostMap {
+    fn cost(&self, cell: CellCoord) -> Option<f32>;
+
+    fn is_walkable(&self, cell: CellCoord) -> bool {
+        self.cost(cell).is_some()
+    }
+}
+
+impl<F: Fn(CellCoord) -> Option<f32>> CostMap for F {
+    fn cost(&self, cell: CellCoord) -> Option<f32> {
+        self(cell)
+    }
+}
+
+// Per-cell entry costs, `None` for blocked cells; cells outside the map are blocked too.
+pub type CostGrid = GridMap<Option<f32>>;
+
+impl CostMap for CostGrid {
+    fn cost(&self, cell: CellCoord) -> Option<f32> {
+        self.get(cell).copied().flatten()
+    }
+}
+
+// Which diagonal steps are allowed past the two orthogonal cells they squeeze between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CornerCutting {
+    Allow,
+    IfOneOpen,
+    #[default]
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathOptions {
+    pub connectivity: Connectivity,
+    pub corner_cutting: CornerCutting,
+}
+
+impl PathOptions {
+    pub fn new(connectivity: Connectivity) -> Self {
+        Self { connectivity, corner_cutting: CornerCutting::default() }
+    }
+
+    pub fn with_corner_cutting(mut self, corner_cutting: CornerCutting) -> Self {
+        self.corner_cutting = corner_cutting;
+        self
+    }
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        Self::new(Connectivity::Eight)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub cells: Vec<CellCoord>,
+    pub cost: f32,
+}
+
+impl Path {
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // Drops intermediate cells wherever a straight segment between the remaining ones only
+    // crosses walkable cells, honouring the corner-cutting rule at exact corner crossings.
+    pub fn smoothed(&self, costs: &impl CostMap, options: &PathOptions) -> Vec<CellCoord> {
+        let Some(&first) = self.cells.first() else {
+            return Vec::new();
+        };
+        let mut waypoints = vec![first];
+        let mut anchor = 0;
+        while anchor + 1 < self.cells.len() {
+            let mut next = anchor + 1;
+            while next + 1 < self.cells.len() && segment_is_clear(costs, self.cells[anchor], self.cells[next + 1], options) {
+                next += 1;
+            }
+            waypoints.push(self.cells[next]);
+            anchor = next;
+        }
+        waypoints
+    }
+}
+
+impl CornerCutting {
+    fn permits(self, costs: &impl CostMap, horizontal: CellCoord, vertical: CellCoord) -> bool {
+        match self {
+            CornerCutting::Allow => true,
+            CornerCutting::IfOneOpen => costs.is_walkable(horizontal) || costs.is_walkable(vertical),
+            CornerCutting::Never => costs.is_walkable(horizontal) && costs.is_walkable(vertical),
+        }
+    }
+}
+
+// Walkable neighbours of `cell` paired with the length of the step to reach them.
+fn moves<'a>(
+    grid: &'a Grid,
+    costs: &'a impl CostMap,
+    cell: CellCoord,
+    options: &'a PathOptions,
+) -> impl Iterator<Item = (CellCoord, f32)> + 'a {
+    options.connectivity.offsets().iter().filter_map(move |&(delta_col, delta_row)| {
+        let neighbor = cell.offset(delta_col, delta_row);
+        if !grid.in_bounds(neighbor) || !costs.is_walkable(neighbor) {
+            return None;
+        }
+        if delta_col != 0 && delta_row != 0 {
+            let (horizontal, vertical) = (cell.offset(delta_col, 0), cell.offset(0, delta_row));
+            options.corner_cutting.permits(costs, horizontal, vertical).then_some((neighbor, SQRT_2))
+        } else {
+            Some((neighbor, 1.0))
+        }
+    })
+}
+
+fn octile_distance(a: CellCoord, b: CellCoord) -> f32 {
+    let dx = a.col.abs_diff(b.col) as f32;
+    let dy = a.row.abs_diff(b.row) as f32;
+    dx.max(dy) - dx.min(dy) + SQRT_2 * dx.min(dy)
+}
+
+#[derive(PartialEq)]
+struct OpenEntry {
+    priority: f32,
+    index: usize,
+}
+
+impl Eq for OpenEntry {}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority).then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+struct Search {
+    distances: Vec<Option<f32>>,
+    parents: Vec<Option<CellCoord>>,
+}
+
+// Best-first search from `sources`. `goal` stops the search early and drives the heuristic;
+// `towards_sources` charges each step for the cell it leaves instead of the one it enters,
+// giving the cost of walking back to the nearest source.
+fn search(
+    grid: &Grid,
+    costs: &impl CostMap,
+    sources: &[CellCoord],
+    goal: Option<CellCoord>,
+    heuristic: fn(CellCoord, CellCoord) -> f32,
+    towards_sources: bool,
+    options: &PathOptions,
+) -> Search {
+    let mut result = Search {
+        distances: vec![None; grid.cell_count()],
+        parents: vec![None; grid.cell_count()],
+    };
+    let mut closed = vec![false; grid.cell_count()];
+    let mut open = BinaryHeap::new();
+    let estimate = |cell: CellCoord| goal.map_or(0.0, |goal| heuristic(cell, goal));
+
+    for &source in sources {
+        let Some(index) = grid.index(source) else {
+            continue;
+        };
+        if costs.is_walkable(source) && result.distances[index].is_none() {
+            result.distances[index] = Some(0.0);
+            open.push(OpenEntry { priority: estimate(source), index });
+        }
+    }
+
+    while let Some(OpenEntry { index, .. }) = open.pop() {
+        if closed[index] {
+            continue;
+        }
+        closed[index] = true;
+        let Some(cell) = grid.cell_from_index(index) else {
+            continue;
+        };
+        if goal == Some(cell) {
+            break;
+        }
+
+        let distance = result.distances[index].unwrap_or(0.0);
+        for (neighbor, step) in moves(grid, costs, cell, options) {
+            let Some(neighbor_index) = grid.index(neighbor) else {
+                continue;
+            };
+            let charged = if towards_sources { cell } else { neighbor };
+            let tentative = distance + step * costs.cost(charged).unwrap_or(1.0);
+            if closed[neighbor_index] || result.distances[neighbor_index].is_some_and(|known| known <= tentative) {
+                continue;
+            }
+            result.distances[neighbor_index] = Some(tentative);
+            result.parents[neighbor_index] = Some(cell);
+            open.push(OpenEntry { priority: tentative + estimate(neighbor), index: neighbor_index });
+        }
+    }
+    result
+}
+
+fn trace_path(grid: &Grid, search: &Search, goal: CellCoord) -> Option<Path> {
+    let cost = search.distances[grid.index(goal)?]?;
+    let mut cells = vec![goal];
+    let mut current = goal;
+    while let Some(parent) = grid.index(current).and_then(|index| search.parents[index]) {
+        cells.push(parent);
+        current = parent;
+    }
+    cells.reverse();
+    Some(Path { cells, cost })
+}
+
+pub fn astar(grid: &Grid, costs: &impl CostMap, start: CellCoord, goal: CellCoord, options: &PathOptions) -> Option<Path> {
+    let heuristic: fn(CellCoord, CellCoord) -> f32 = match options.connectivity {
+        Connectivity::Four => |a: CellCoord, b: CellCoord| a.manhattan_distance(b) as f32,
+        Connectivity::Eight => octile_distance,
+    };
+    let search = search(grid, costs, &[start], Some(goal), heuristic, false, options);
+    trace_path(grid, &search, goal)
+}
+
+pub fn dijkstra(grid: &Grid, costs: &impl CostMap, start: CellCoord, goal: CellCoord, options: &PathOptions) -> Option<Path> {
+    let search = search(grid, costs, &[start], Some(goal), |_, _| 0.0, false, options);
+    trace_path(grid, &search, goal)
+}
+
+// For every cell, the distance to the nearest target and the neighbour to step to next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowField {
+    grid: Grid,
+    distances: Vec<Option<f32>>,
+    next: Vec<Option<CellCoord>>,
+}
+
+impl FlowField {
+    // Unweighted: every walkable cell costs one step, diagonals included.
+    pub fn breadth_first(grid: &Grid, costs: &impl CostMap, targets: &[CellCoord], options: &PathOptions) -> Self {
+        let mut distances = vec![None; grid.cell_count()];
+        let mut next = vec![None; grid.cell_count()];
+        let mut queue = VecDeque::new();
+        for &target in targets {
+            if let Some(index) = grid.index(target) {
+                if costs.is_walkable(target) && distances[index].is_none() {
+                    distances[index] = Some(0.0);
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            let distance = grid.index(cell).and_then(|index| distances[index]).unwrap_or(0.0);
+            for (neighbor, _) in moves(grid, costs, cell, options) {
+                let Some(index) = grid.index(neighbor) else {
+                    continue;
+                };
+                if distances[index].is_none() {
+                    distances[index] = Some(distance + 1.0);
+                    next[index] = Some(cell);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        Self { grid: *grid, distances, next }
+    }
+
+    // Weighted by the cost map, as Dijkstra from all targets at once.
+    pub fn weighted(grid: &Grid, costs: &impl CostMap, targets: &[CellCoord], options: &PathOptions) -> Self {
+        let search = search(grid, costs, targets, None, |_, _| 0.0, true, options);
+        Self { grid: *grid, distances: search.distances, next: search.parents }
+    }
+
+    pub fn distance(&self, cell: CellCoord) -> Option<f32> {
+        self.grid.index(cell).and_then(|index| self.distances[index])
+    }
+
+    pub fn next_cell(&self, cell: CellCoord) -> Option<CellCoord> {
+        self.grid.index(cell).and_then(|index| self.next[index])
+    }
+
+    pub fn direction(&self, cell: CellCoord) -> Option<(i32, i32)> {
+        self.next_cell(cell).map(|next| (next.col - cell.col, next.row - cell.row))
+    }
+
+    pub fn path_from(&self, start: CellCoord) -> Option<Path> {
+        let cost = self.distance(start)?;
+        let mut cells = vec![start];
+        let mut current = start;
+        while let Some(next) = self.next_cell(current) {
+            cells.push(next);
+            current = next;
+        }
+        Some(Path { cells, cost })
+    }
+}
+
+// A straight segment between two cell centres is clear when every cell it touches is walkable
+// and each exact corner it crosses satisfies the corner-cutting rule.
+fn segment_is_clear(costs: &impl CostMap, from: CellCoord, to: CellCoord, options: &PathOptions) -> bool {
+    walk_supercover(from, to, |cell, corner| {
+        let corner_passable =
+            corner.is_none_or(|[horizontal, vertical]| options.corner_cutting.permits(costs, horizontal, vertical));
+        corner_passable && costs.is_walkable(cell)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::ascii_map;
+
+    // '#' is blocked, digits are entry costs, anything else costs 1.
+    fn parse(rows: &[&str]) -> (Grid, CostGrid) {
+        let map = ascii_map(rows);
+        let costs = map.map(|_, &symbol| match symbol {
+            b'#' => None,
+            digit if digit.is_ascii_digit() => Some((digit - b'0') as f32),
+            _ => Some(1.0),
+        });
+        (Grid::new(map.cols(), map.rows(), 1.0, 1.0), costs)
+    }
+
+    #[test]
+    fn astar_matches_dijkstra_around_walls() {
+        let (grid, costs) = parse(&["......", ".####.", "....#.", "###.#.", "......"]);
+        let start = CellCoord::new(0, 0);
+        let goal = CellCoord::new(0, 4);
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            let options = PathOptions::new(connectivity);
+            let fast = astar(&grid, &costs, start, goal, &options).unwrap();
+            let exhaustive = dijkstra(&grid, &costs, start, goal, &options).unwrap();
+            assert!((fast.cost - exhaustive.cost).abs() < 1e-4);
+            assert_eq!(fast.cells.first(), Some(&start));
+            assert_eq!(fast.cells.last(), Some(&goal));
+            assert!(fast.cells.iter().all(|&cell| costs.is_walkable(cell)));
+        }
+    }
+
+    #[test]
+    fn costs_steer_the_route() {
+        let (grid, costs) = parse(&["...", ".9.", "..."]);
+        let path = astar(&grid, &costs, CellCoord::new(0, 1), CellCoord::new(2, 1), &PathOptions::new(Connectivity::Four)).unwrap();
+        assert!(!path.cells.contains(&CellCoord::new(1, 1)));
+        assert_eq!(path.cost, 4.0);
+    }
+
+    #[test]
+    fn corner_cutting_rules() {
+        let (grid, costs) = parse(&[".#", ".."]);
+        let start = CellCoord::new(0, 0);
+        let goal = CellCoord::new(1, 1);
+        let never = PathOptions::default();
+        let one_open = never.with_corner_cutting(CornerCutting::IfOneOpen);
+        assert_eq!(astar(&grid, &costs, start, goal, &never).unwrap().len(), 3);
+        assert_eq!(astar(&grid, &costs, start, goal, &one_open).unwrap().len(), 2);
+
+        let (grid, costs) = parse(&[".#", "#."]);
+        assert!(astar(&grid, &costs, start, goal, &one_open).is_none());
+        let allow = never.with_corner_cutting(CornerCutting::Allow);
+        assert_eq!(astar(&grid, &costs, start, goal, &allow).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let (grid, costs) = parse(&[".#.", ".#.", ".#."]);
+        assert!(astar(&grid, &costs, CellCoord::new(0, 0), CellCoord::new(2, 2), &PathOptions::default()).is_none());
+    }
+
+    #[test]
+    fn flow_fields_lead_to_the_nearest_target() {
+        let (grid, costs) = parse(&["....", ".##.", "...."]);
+        let targets = [CellCoord::new(0, 0), CellCoord::new(3, 2)];
+        let options = PathOptions::new(Connectivity::Four);
+        let field = FlowField::breadth_first(&grid, &costs, &targets, &options);
+        assert_eq!(field.distance(CellCoord::new(3, 0)), Some(2.0));
+        assert_eq!(field.distance(CellCoord::new(1, 1)), None);
+        let path = field.path_from(CellCoord::new(0, 2)).unwrap();
+        assert_eq!(path.cells.last(), Some(&CellCoord::new(0, 0)));
+        assert_eq!(path.len(), 3);
+
+        let weighted = FlowField::weighted(&grid, &costs, &targets, &options);
+        assert_eq!(weighted.direction(CellCoord::new(3, 1)), Some((0, 1)));
+    }
+
+    #[test]
+    fn smoothing_keeps_corners_only() {
+        let (grid, costs) = parse(&[".....", "...#.", "....."]);
+        let options = PathOptions::new(Connectivity::Four);
+        let path = astar(&grid, &costs, CellCoord::new(0, 2), CellCoord::new(4, 0), &options).unwrap();
+        let waypoints = path.smoothed(&costs, &options);
+        assert_eq!(waypoints.first(), Some(&CellCoord::new(0, 2)));
+        assert_eq!(waypoints.last(), Some(&CellCoord::new(4, 0)));
+        assert!(waypoints.len() < path.len());
+        for pair in waypoints.windows(2) {
+            assert!(segment_is_clear(&costs, pair[0], pair[1], &options));
+        }
+        assert_eq!(grid.cells_to_world(&waypoints).len(), waypoints.len());
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use super::{line::walk_supercover, CellCoord, Grid};
+
+// True when nothing between the two cells blocks sight; the end cells themselves may block,
+// so walls are visible. The test is symmetric: a segment squeezing exactly between two
+// diagonal cells is only stopped when both of them block.
+pub fn has_line_of_sight(from: CellCoord, to: CellCoord, blocks: impl Fn(CellCoord) -> bool) -> bool {
+    walk_supercover(from, to, |cell, corner| {
+        let corner_open = corner.is_none_or(|[horizontal, vertical]| !(blocks(horizontal) && blocks(vertical)));
+        corner_open && (cell == to || !blocks(cell))
+    })
+}
+
+// A slope of `numerator / denominator` (denominator > 0), kept exact so cells on the boundary
+// of a shadow land on the same side from every direction.
+#[derive(Debug, Clone, Copy)]
+struct Slope {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Slope {
+    fn new(numerator: i64, denominator: i64) -> Self {
+        Self { numerator, denominator }
+    }
+
+    // Slope through the near-left corner of the cell at (depth, col).
+    fn of_cell(depth: i64, col: i64) -> Self {
+        Self::new(2 * col - 1, 2 * depth)
+    }
+
+    fn first_col(self, depth: i64) -> i64 {
+        (2 * depth * self.numerator + self.denominator).div_euclid(2 * self.denominator)
+    }
+
+    fn last_col(self, depth: i64) -> i64 {
+        -(-(2 * depth * self.numerator - self.denominator)).div_euclid(2 * self.denominator)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    depth: i64,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    fn next(self) -> Row {
+        Row { depth: self.depth + 1, ..self }
+    }
+
+    fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start.denominator >= self.depth * self.start.numerator
+            && col * self.end.denominator <= self.depth * self.end.numerator
+    }
+}
+
+// Symmetric shadowcasting: `reveal` is called for every cell within `radius` of `origin` that
+// is visible from it, once or more. A cell is visible from `origin` exactly when `origin` is
+// visible from the cell. `blocks` must also answer for cells outside any map bounds.
+pub fn field_of_view(
+    origin: CellCoord,
+    radius: u32,
+    blocks: impl Fn(CellCoord) -> bool,
+    mut reveal: impl FnMut(CellCoord),
+) {
+    reveal(origin);
+    let radius = radius as i64;
+    let quadrants: [fn(CellCoord, i64, i64) -> CellCoord; 4] = [
+        |origin, depth, col| origin.offset(col as i32, -depth as i32),
+        |origin, depth, col| origin.offset(depth as i32, col as i32),
+        |origin, depth, col| origin.offset(col as i32, depth as i32),
+        |origin, depth, col| origin.offset(-depth as i32, col as i32),
+    ];
+
+    for transform in quadrants {
+        let cell_at = |depth: i64, col: i64| transform(origin, depth, col);
+        let mut rows = vec![Row { depth: 1, start: Slope::new(-1, 1), end: Slope::new(1, 1) }];
+        while let Some(mut row) = rows.pop() {
+            if row.depth > radius {
+                continue;
+            }
+            let mut previous_blocked = None;
+            for col in row.start.first_col(row.depth)..=row.end.last_col(row.depth) {
+                let cell = cell_at(row.depth, col);
+                let blocked = blocks(cell);
+                let in_radius = col * col + row.depth * row.depth <= radius * radius + radius;
+                if in_radius && (blocked || row.is_symmetric(col)) {
+                    reveal(cell);
+                }
+                if previous_blocked == Some(true) && !blocked {
+                    row.start = Slope::of_cell(row.depth, col);
+                }
+                if previous_blocked == Some(false) && blocked {
+                    let mut next = row.next();
+                    next.end = Slope::of_cell(row.depth, col);
+                    rows.push(next);
+                }
+                previous_blocked = Some(blocked);
+            }
+            if previous_blocked == Some(false) {
+                rows.push(row.next());
+            }
+        }
+    }
+}
+
+// Visible in-bounds cells of `grid`, treating everything outside it as blocking. Without a
+// radius the whole grid is in range.
+pub fn visible_cells(
+    grid: &Grid,
+    origin: CellCoord,
+    radius: Option<u32>,
+    blocks: impl Fn(CellCoord) -> bool,
+) -> HashSet<CellCoord> {
+    let mut visible = HashSet::new();
+    let radius = radius.unwrap_or(grid.cols.max(grid.rows) * 2);
+    field_of_view(
+        origin,
+        radius,
+        |cell| !grid.in_bounds(cell) || blocks(cell),
+        |cell| {
+            if grid.in_bounds(cell) {
+                visible.insert(cell);
+            }
+        },
+    );
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::ascii_map;
+
+    fn parse(rows: &[&str]) -> (Grid, HashSet<CellCoord>) {
+        let map = ascii_map(rows);
+        let walls = map.iter().filter(|&(_, &symbol)| symbol == b'#').map(|(cell, _)| cell).collect();
+        (Grid::new(map.cols(), map.rows(), 1.0, 1.0), walls)
+    }
+
+    #[test]
+    fn open_room_is_fully_visible() {
+        let (grid, walls) = parse(&["#####", "#...#", "#...#", "#####"]);
+        let visible = visible_cells(&grid, CellCoord::new(1, 1), None, |cell| walls.contains(&cell));
+        assert_eq!(visible.len(), grid.cell_count());
+    }
+
+    #[test]
+    fn walls_cast_shadows() {
+        let (grid, walls) = parse(&[".......", "...#...", "......."]);
+        let visible = visible_cells(&grid, CellCoord::new(0, 1), None, |cell| walls.contains(&cell));
+        assert!(visible.contains(&CellCoord::new(3, 1)));
+        assert!(!visible.contains(&CellCoord::new(5, 1)));
+        assert!(!visible.contains(&CellCoord::new(6, 1)));
+        assert!(visible.contains(&CellCoord::new(6, 0)));
+    }
+
+    #[test]
+    fn visibility_is_symmetric() {
+        let (grid, walls) = parse(&["........", "..#..#..", ".....#..", "#.......", "...##..."]);
+        let blocks = |cell: CellCoord| walls.contains(&cell);
+        let floors: Vec<_> = grid.cells().filter(|cell| !blocks(*cell)).collect();
+        for &a in &floors {
+            let from_a = visible_cells(&grid, a, None, blocks);
+            for &b in &floors {
+                let from_b = visible_cells(&grid, b, None, blocks);
+                assert_eq!(from_a.contains(&b), from_b.contains(&a), "{:?} <-> {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn radius_limits_the_view() {
+        let grid = Grid::new(21, 21, 1.0, 1.0);
+        let visible = visible_cells(&grid, CellCoord::new(10, 10), Some(3), |_| false);
+        assert!(visible.iter().all(|cell| cell.chebyshev_distance(CellCoord::new(10, 10)) <= 3));
+        assert!(visible.contains(&CellCoord::new(13, 10)));
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let (_, walls) = parse(&["....", ".#..", "....", "..#."]);
+        let blocks = |cell: CellCoord| walls.contains(&cell);
+        assert!(!has_line_of_sight(CellCoord::new(0, 1), CellCoord::new(3, 1), blocks));
+        assert!(has_line_of_sight(CellCoord::new(0, 0), CellCoord::new(3, 0), blocks));
+        assert!(has_line_of_sight(CellCoord::new(0, 1), CellCoord::new(1, 1), blocks));
+        assert_eq!(
+            has_line_of_sight(CellCoord::new(0, 0), CellCoord::new(3, 3), blocks),
+            has_line_of_sight(CellCoord::new(3, 3), CellCoord::new(0, 0), blocks)
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use context::structure::KTable;
-
 pub mod logger;
 pub mod graphics;
-pub mod context;
\ No newline at end of file
+pub mod context;
+pub mod error;
+pub mod grid;
+
+pub use error::{Error, Result};
\ No newline at end of file